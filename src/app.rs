@@ -1,148 +1,1395 @@
-use crossterm::event::KeyCode;
+//! The timer state machine.
 
-use crate::utils::{format_secs, MyResult};
+use chrono::{DateTime, Local, NaiveTime};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
-#[derive(Clone)]
+use crate::calendar::WorkingHours;
+use crate::checkpoint::Checkpoint;
+use crate::clock::{Clock, SystemClock};
+use crate::events::TimerEvent;
+use crate::micro_break::MicroBreak;
+use crate::phase::{BreakRule, Phase, PhaseKind, Sequence};
+use crate::state::State;
+use crate::task::{Task, TaskList};
+use crate::utils::{ceil_secs, format_secs, secs_until, time_to_next_second, MyResult};
+
+/// Input to the timer, see [`App::on`].
+#[derive(Clone, Debug, PartialEq)]
 pub enum AppEvent {
+    /// Goes back to the first phase, paused.
     Stop,
+    /// Ends the app.
     Quit,
+    /// Does nothing.
     None,
+    /// Pauses or resumes, or acknowledges the end of a phase in overtime.
     Toggle,
+    /// Moves on to the next [`AutoStart`] setting.
+    CycleAutoStart,
+    /// Ends the phase early, or a phase counting up as completed.
+    Skip,
+    /// Makes the phase longer by the extend duration.
+    Extend,
+    /// Logs the urge to do something else.
+    InternalInterruption,
+    /// Logs someone or something else interrupting.
+    ExternalInterruption,
 }
 
-#[derive(Clone)]
+/// Where the timer is at, see [`App::get_status`].
+#[derive(Clone, Debug, PartialEq)]
 pub enum AppStatus {
+    /// The phase is counting down, or up.
     Running, // TODO better variable name
+    /// The phase waits to be started or resumed.
     Paused,
+    /// Counting down to the auto-start of the next phase.
+    Starting,
+    /// The phase is over but hasn't been acknowledged yet.
+    Overtime,
+    /// Counting down to the start of the session at a time of day.
+    Scheduled,
+    /// The app is ending.
     Quit,
 }
 
-impl AppEvent {
-    pub fn from_keycode(keycode: KeyCode) -> MyResult<AppEvent> {
-        match keycode {
-            KeyCode::Char(char) => match char {
-                'q' => Ok(AppEvent::Quit),
-                ' ' => Ok(AppEvent::Toggle),
-                's' => Ok(AppEvent::Stop),
-                _ => Ok(AppEvent::None),
-            },
-            _ => Ok(AppEvent::None),
+/// Which phases start on their own once the previous one ends.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AutoStart {
+    /// Every phase is started by hand.
+    Neither,
+    /// Rest phases start on their own.
+    Breaks,
+    /// Focus phases start on their own.
+    Work,
+    /// Every phase starts on its own.
+    Both,
+}
+
+impl AutoStart {
+    fn starts_breaks(&self) -> bool {
+        matches!(self, AutoStart::Breaks | AutoStart::Both)
+    }
+
+    fn starts_work(&self) -> bool {
+        matches!(self, AutoStart::Work | AutoStart::Both)
+    }
+
+    fn next(&self) -> AutoStart {
+        match self {
+            AutoStart::Neither => AutoStart::Breaks,
+            AutoStart::Breaks => AutoStart::Work,
+            AutoStart::Work => AutoStart::Both,
+            AutoStart::Both => AutoStart::Neither,
+        }
+    }
+
+    /// Shown in the UI and taken by [`AutoStart::parse`].
+    pub fn label(&self) -> &'static str {
+        match self {
+            AutoStart::Neither => "off",
+            AutoStart::Breaks => "breaks",
+            AutoStart::Work => "work",
+            AutoStart::Both => "all",
+        }
+    }
+
+    /// Parses one of the labels, "off", "breaks", "work" or "all".
+    pub fn parse(label: &str) -> MyResult<AutoStart> {
+        [
+            AutoStart::Neither,
+            AutoStart::Breaks,
+            AutoStart::Work,
+            AutoStart::Both,
+        ]
+        .into_iter()
+        .find(|auto_start| auto_start.label() == label.trim())
+        .ok_or_else(|| {
+            format!(
+                "invalid auto-start `{}`, expected off, breaks, work or all",
+                label.trim()
+            )
+            .into()
+        })
+    }
+}
+
+/// How a phase ended.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PhaseOutcome {
+    /// Its time was up, or it was ended by hand while counting up.
+    Completed,
+    /// It was ended early.
+    Skipped,
+}
+
+/// What interrupted a focus phase.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InterruptionKind {
+    /// The urge to do something else.
+    Internal,
+    /// Someone or something else.
+    External,
+    /// The terminal lost focus.
+    Away,
+}
+
+/// An interruption logged during a phase, which kept running.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interruption {
+    /// What interrupted.
+    pub kind: InterruptionKind,
+    /// When it was logged.
+    pub at: DateTime<Local>,
+}
+
+/// Why a phase was paused.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PauseReason {
+    /// A meeting.
+    Meeting,
+    /// A call.
+    Call,
+    /// A distraction.
+    Distraction,
+    /// Something personal.
+    Personal,
+    /// The terminal lost focus.
+    Away,
+}
+
+impl PauseReason {
+    /// The reasons offered to pick from, [`PauseReason::Away`] being set
+    /// automatically.
+    pub const ALL: [PauseReason; 4] = [
+        PauseReason::Meeting,
+        PauseReason::Call,
+        PauseReason::Distraction,
+        PauseReason::Personal,
+    ];
+
+    /// Shown in the UI.
+    pub fn label(&self) -> &'static str {
+        match self {
+            PauseReason::Meeting => "Meeting",
+            PauseReason::Call => "Call",
+            PauseReason::Distraction => "Distraction",
+            PauseReason::Personal => "Personal",
+            PauseReason::Away => "Away",
+        }
+    }
+}
+
+/// What happens to a running focus phase when the terminal loses focus.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FocusMode {
+    /// Nothing.
+    Off,
+    /// The phase is paused, and resuming it is offered once back.
+    Pause,
+    /// An away interruption is logged and the phase keeps running.
+    Interrupt,
+}
+
+/// A pause within a phase.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pause {
+    /// When the phase was paused.
+    pub started_at: DateTime<Local>,
+    /// When the phase was resumed, `None` while still paused.
+    pub ended_at: Option<DateTime<Local>>,
+    /// Why the phase was paused, if given.
+    pub reason: Option<PauseReason>,
+}
+
+impl Pause {
+    /// In seconds, up to `now` while still paused.
+    pub fn get_duration(&self, now: DateTime<Local>) -> u64 {
+        let ended_at = self.ended_at.unwrap_or(now);
+
+        (ended_at - self.started_at).num_seconds().max(0) as u64
+    }
+}
+
+/// A phase that ended, see [`App::get_history`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhaseRecord {
+    /// Focus or rest.
+    pub kind: PhaseKind,
+    /// Completed or skipped.
+    pub outcome: PhaseOutcome,
+    /// Seconds spent in overtime.
+    pub overrun: u64,
+    /// When the phase ended.
+    pub ended_at: DateTime<Local>,
+    /// Logged while it ran.
+    pub interruptions: Vec<Interruption>,
+    /// Index in the task list of the task worked on, for focus phases.
+    pub task: Option<usize>,
+    /// Taken while it ran.
+    pub pauses: Vec<Pause>,
+    /// Seconds actually spent running, pauses excluded.
+    pub active: u64,
+}
+
+// End of the running phase, or its start for phases counting up. The
+// monotonic instant is immune to wall clock adjustments but doesn't advance
+// while the machine is suspended, the wall clock time does, so the remaining
+// time is the smaller of the two and the elapsed time the larger.
+#[derive(Clone)]
+struct Deadline {
+    instant: Instant,
+    wall: SystemTime,
+}
+
+impl Deadline {
+    fn after(clock: &dyn Clock, duration: Duration) -> Deadline {
+        Deadline {
+            instant: clock.instant() + duration,
+            wall: clock.system_time() + duration,
+        }
+    }
+
+    fn before(clock: &dyn Clock, duration: Duration) -> Deadline {
+        let now = clock.instant();
+
+        Deadline {
+            instant: now.checked_sub(duration).unwrap_or(now),
+            wall: clock.system_time() - duration,
         }
     }
+
+    fn elapsed(&self, clock: &dyn Clock) -> Duration {
+        let monotonic = clock.instant().saturating_duration_since(self.instant);
+        let wall = clock
+            .system_time()
+            .duration_since(self.wall)
+            .unwrap_or(Duration::ZERO);
+
+        monotonic.max(wall)
+    }
+
+    fn remaining(&self, clock: &dyn Clock) -> Duration {
+        let monotonic = self.instant.saturating_duration_since(clock.instant());
+        let wall = self
+            .wall
+            .duration_since(clock.system_time())
+            .unwrap_or(Duration::ZERO);
+
+        monotonic.min(wall)
+    }
+
+    fn extend(&mut self, duration: Duration) {
+        self.instant += duration;
+        self.wall += duration;
+    }
+
+    // as if set `duration` earlier
+    fn rewind(&mut self, duration: Duration) {
+        self.instant = self.instant.checked_sub(duration).unwrap_or(self.instant);
+        self.wall -= duration;
+    }
 }
 
+/// The timer state machine.
+///
+/// It keeps no thread of its own: call [`App::on_tick`] whenever the
+/// displayed second may have changed ([`App::get_time_to_next_second`]),
+/// feed it input with [`App::on`] and drain the resulting [`TimerEvent`]s
+/// with [`App::take_events`].
 #[derive(Clone)]
 pub struct App {
-    work_duration: u64,  // in seconds
-    break_duration: u64, // in seconds
-    progress: u64,       // in seconds
-    is_working: bool,
+    sequence: Sequence,
+    position: usize,            // of the current phase in the sequence
+    progress: u64,              // remaining seconds while paused, elapsed ones when counting up
+    phase_duration: u64,        // in seconds, including extensions
+    deadline: Option<Deadline>, // set while running
+    extend_duration: u64,       // in seconds
+    extensions: u64,            // of the current phase
+    max_break_extensions: u64,
+    break_rule: BreakRule, // for breaks following a phase counting up
+    overtime: bool,        // keep counting once the phase is over until acknowledged
+    strict: bool,          // breaks can't be skipped, stopped or paused
+    checkpoints: Vec<Checkpoint>,
+    micro_break: Option<MicroBreak>,
+    micro_break_deadline: Option<Deadline>, // set while a micro-break is due
+    working_hours: Option<WorkingHours>,
+    working: bool,       // within working hours as of the last tick
+    checked_until: u64,  // seconds into the current phase checkpoints were evaluated up to
+    phase_started: bool, // the current phase ran already, so a time of day end is settled
+    history: Vec<PhaseRecord>,
+    interruptions: Vec<Interruption>, // of the current phase
+    pauses: Vec<Pause>,               // of the current phase
+    focus_mode: FocusMode,
+    paused_while_away: bool, // paused because the terminal lost focus
+    tasks: TaskList,
+    daily_goal: u64,         // completed focus phases a day, 0 disables it
+    events: Vec<TimerEvent>, // until taken by `take_events`
+    auto_start: AutoStart,
+    auto_start_countdown: u64, // in seconds, 0 starts the next phase right away
+    countdown_deadline: Option<Deadline>, // set while starting or scheduled
+    scheduled_at: Option<NaiveTime>,
     status: AppStatus,
+    clock: Arc<dyn Clock>,
 }
 
 impl App {
-    pub fn new(work_duration: u64, break_duration: u64) -> App {
+    /// A timer running the first phase of `sequence`, with every option off.
+    pub fn new(sequence: Sequence) -> App {
+        let mut app = App::idle(sequence, Arc::new(SystemClock));
+
+        app.start();
+
+        app
+    }
+
+    // not started yet, the first phase's duration as of the clock's time
+    fn idle(sequence: Sequence, clock: Arc<dyn Clock>) -> App {
+        let duration = sequence.get(0).get_duration_at(clock.now().time());
+
         App {
-            work_duration,
-            break_duration,
-            progress: work_duration,
-            is_working: true,
-            status: AppStatus::Running,
+            sequence,
+            position: 0,
+            progress: duration,
+            phase_duration: duration,
+            extend_duration: 0,
+            extensions: 0,
+            max_break_extensions: 0,
+            break_rule: BreakRule::Fraction(5),
+            overtime: false,
+            strict: false,
+            checkpoints: Vec::new(),
+            micro_break: None,
+            micro_break_deadline: None,
+            working_hours: None,
+            working: false,
+            checked_until: 0,
+            phase_started: false,
+            history: Vec::new(),
+            interruptions: Vec::new(),
+            pauses: Vec::new(),
+            focus_mode: FocusMode::Off,
+            paused_while_away: false,
+            tasks: TaskList::new(),
+            daily_goal: 0,
+            events: Vec::new(),
+            deadline: None,
+            auto_start: AutoStart::Neither,
+            auto_start_countdown: 0,
+            countdown_deadline: None,
+            scheduled_at: None,
+            status: AppStatus::Paused,
+            clock,
+        }
+    }
+
+    /// Configures a timer for `sequence`, started by [`AppBuilder::build`].
+    pub fn builder(sequence: Sequence) -> AppBuilder {
+        AppBuilder {
+            app: App::idle(sequence, Arc::new(SystemClock)),
+        }
+    }
+
+    fn remaining(&self) -> Duration {
+        match &self.deadline {
+            Some(deadline) => deadline.remaining(self.clock.as_ref()),
+            None => Duration::from_secs(self.progress),
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        match &self.deadline {
+            Some(deadline) => deadline.elapsed(self.clock.as_ref()),
+            None => Duration::from_secs(self.progress),
+        }
+    }
+
+    fn start_deadline(&self) -> Deadline {
+        let phase = self.get_phase();
+        let progress = Duration::from_secs(self.progress);
+
+        let clock = self.clock.as_ref();
+
+        if phase.is_count_up() {
+            Deadline::before(clock, progress)
+        } else if phase.get_until().is_some() {
+            // the end is fixed, time spent paused doesn't push it back, and
+            // once past it the phase is over rather than ending the next day
+            let secs = phase.get_duration_at(clock.now().time());
+            let secs = if secs > self.phase_duration { 0 } else { secs };
+
+            Deadline::after(clock, Duration::from_secs(secs))
+        } else {
+            Deadline::after(clock, progress)
+        }
+    }
+
+    fn set_progress(&mut self, secs: u64) {
+        self.progress = secs;
+        self.phase_duration = secs;
+        self.extensions = 0;
+        self.checked_until = 0;
+        self.phase_started = false;
+
+        if self.deadline.is_some() {
+            self.deadline = Some(self.start_deadline());
+        }
+    }
+
+    fn start(&mut self) {
+        let first_run = !self.phase_started;
+
+        self.paused_while_away = false;
+        self.end_pause();
+        self.countdown_deadline = None;
+        self.scheduled_at = None;
+
+        // a phase ending at a time of day lasts from when it first starts
+        if self.get_phase().get_until().is_some() && first_run {
+            let secs = self.get_phase().get_duration_at(self.clock.now().time());
+
+            self.progress = secs;
+            self.phase_duration = secs;
+            self.checked_until = 0;
+        }
+
+        self.phase_started = true;
+        self.deadline = Some(self.start_deadline());
+        self.status = AppStatus::Running;
+
+        if first_run {
+            self.emit(TimerEvent::PhaseRunning(self.get_phase().clone()));
+        }
+    }
+
+    fn end_pause(&mut self) {
+        if let Some(pause) = self.pauses.last_mut() {
+            pause.ended_at.get_or_insert(self.clock.now());
+        }
+    }
+
+    fn pause(&mut self) {
+        // cancelling the auto-start countdown doesn't pause a running phase
+        if matches!(self.status, AppStatus::Running) {
+            self.pauses.push(Pause {
+                started_at: self.clock.now(),
+                ended_at: None,
+                reason: None,
+            });
+            self.emit(TimerEvent::Paused);
+        }
+
+        self.progress = self.get_progress_secs();
+        self.deadline = None;
+        self.countdown_deadline = None;
+        self.status = AppStatus::Paused;
+    }
+
+    fn auto_start_next(&mut self) {
+        let auto_start = if self.get_phase().is_focus() {
+            self.auto_start.starts_work()
+        } else {
+            self.auto_start.starts_breaks()
+        };
+
+        // outside working hours, phases only start by hand
+        if !auto_start || !self.is_working_time() {
+            self.status = AppStatus::Paused;
+        } else if self.auto_start_countdown == 0 {
+            self.start();
+        } else {
+            self.countdown_deadline = Some(Deadline::after(
+                self.clock.as_ref(),
+                Duration::from_secs(self.auto_start_countdown),
+            ));
+            self.status = AppStatus::Starting;
+        }
+    }
+
+    fn extend(&mut self) {
+        if self.get_phase().is_count_up() || matches!(self.status, AppStatus::Overtime) {
+            return;
+        }
+
+        if !self.get_phase().is_focus() && self.extensions >= self.max_break_extensions {
+            return;
+        }
+
+        let extension = Duration::from_secs(self.extend_duration);
+
+        self.progress += self.extend_duration;
+        self.phase_duration += self.extend_duration;
+        self.extensions += 1;
+
+        if let Some(deadline) = &mut self.deadline {
+            deadline.extend(extension);
+        }
+    }
+
+    fn record_phase(&mut self, outcome: PhaseOutcome) {
+        let now = self.clock.now();
+        let active = if self.get_phase().is_count_up() {
+            self.get_progress_secs()
+        } else if self.get_phase().get_until().is_some() {
+            // pauses don't push the end of the phase back, so the ones resumed
+            // from took from it, while an ongoing one froze the remaining time
+            let paused: u64 = self
+                .pauses
+                .iter()
+                .filter(|pause| pause.ended_at.is_some())
+                .map(|pause| pause.get_duration(now))
+                .sum();
+
+            self.phase_duration
+                .saturating_sub(self.get_remaining_secs())
+                .saturating_sub(paused)
+                + self.get_overtime_secs()
+        } else {
+            self.phase_duration
+                .saturating_sub(self.get_remaining_secs())
+                + self.get_overtime_secs()
+        };
+
+        self.end_pause();
+        self.history.push(PhaseRecord {
+            kind: self.get_phase().get_kind(),
+            outcome,
+            overrun: self.get_overtime_secs(),
+            ended_at: now,
+            interruptions: std::mem::take(&mut self.interruptions),
+            task: self
+                .get_phase()
+                .is_focus()
+                .then(|| self.tasks.get_active())
+                .flatten(),
+            pauses: std::mem::take(&mut self.pauses),
+            active,
+        });
+
+        if self.get_phase().is_focus()
+            && matches!(outcome, PhaseOutcome::Completed)
+            && self.daily_goal > 0
+            && self.get_completed_today() == self.daily_goal
+        {
+            self.emit(TimerEvent::GoalReached {
+                completed: self.daily_goal,
+            });
         }
     }
 
-    fn update_progress<F>(&mut self, mut f: F)
-    where
-        F: FnMut(bool),
-    {
-        if matches!(self.status, AppStatus::Running) && self.progress > 0 {
-            self.progress -= 1;
+    fn emit(&mut self, event: TimerEvent) {
+        self.events.push(event);
+    }
+
+    fn next_position(&self) -> usize {
+        (self.position + 1) % self.sequence.len()
+    }
+
+    fn update_progress(&mut self) {
+        // micro-breaks run their course even if the phase is paused meanwhile
+        if self
+            .micro_break_deadline
+            .as_ref()
+            .is_some_and(|deadline| deadline.remaining(self.clock.as_ref()).is_zero())
+        {
+            self.end_micro_break();
+        }
+
+        let working = self.is_working_time();
+        let was_working = std::mem::replace(&mut self.working, working);
+
+        // the end of the working day stops the timer, starting it again is up to the user
+        if was_working
+            && !self.working
+            && matches!(self.status, AppStatus::Running | AppStatus::Starting)
+        {
+            self.pause();
+            self.emit(TimerEvent::EndOfDay);
+
+            return;
         }
 
-        if self.progress == 0 {
-            if self.is_working {
-                // start break timer
-                self.progress = self.break_duration;
-                self.is_working = false;
-                f(true);
-            } else {
-                // start work timer
-                self.progress = self.work_duration;
-                self.is_working = true;
-                f(false);
+        if !matches!(
+            self.status,
+            AppStatus::Running | AppStatus::Starting | AppStatus::Scheduled
+        ) {
+            return;
+        }
+
+        if let Some(countdown_deadline) = &self.countdown_deadline {
+            if countdown_deadline.remaining(self.clock.as_ref()).is_zero() {
+                let scheduled = matches!(self.status, AppStatus::Scheduled);
+
+                // like auto-starts, scheduled starts wait for working hours
+                if scheduled && !self.is_working_time() {
+                    self.countdown_deadline = None;
+                    self.scheduled_at = None;
+                    self.status = AppStatus::Paused;
+
+                    return;
+                }
+
+                self.start();
+
+                if scheduled {
+                    self.emit(TimerEvent::ScheduledStart(self.get_phase().clone()));
+                }
             }
 
+            return;
+        }
+
+        self.emit(TimerEvent::Tick {
+            progress: self.get_progress_secs(),
+        });
+        self.check_checkpoints();
+
+        if self.get_phase().is_count_up() || !self.remaining().is_zero() {
+            return;
+        }
+
+        // the phase is over, even if it ended while the machine was suspended
+        self.emit(TimerEvent::PhaseCompleted {
+            phase: self.get_phase().clone(),
+            next: self.sequence.get(self.next_position()).clone(),
+        });
+
+        if self.overtime {
+            // the next phase is announced now but only starts once acknowledged
+            self.status = AppStatus::Overtime;
+
+            return;
+        }
+
+        self.record_phase(PhaseOutcome::Completed);
+        self.next_phase();
+    }
+
+    // checkpoints and micro-breaks reached since the last tick
+    fn check_checkpoints(&mut self) {
+        let elapsed = self.get_elapsed_secs();
+        let duration = (!self.get_phase().is_count_up()).then_some(self.phase_duration);
+        let crossed: Vec<Checkpoint> = self
+            .checkpoints
+            .iter()
+            .filter(|checkpoint| checkpoint.is_crossed(self.checked_until, elapsed, duration))
+            .cloned()
+            .collect();
+        let micro_break = self
+            .micro_break
+            .as_ref()
+            .filter(|micro_break| {
+                self.get_phase().is_focus()
+                    && micro_break.is_due(self.checked_until, elapsed, duration)
+            })
+            .map(|micro_break| micro_break.get_duration());
+
+        self.checked_until = elapsed;
+
+        for checkpoint in crossed {
+            self.emit(TimerEvent::Checkpoint(checkpoint));
+        }
+
+        if let Some(secs) = micro_break {
+            self.micro_break_deadline = Some(Deadline::after(
+                self.clock.as_ref(),
+                Duration::from_secs(secs),
+            ));
+            self.emit(TimerEvent::MicroBreakStarted { secs });
+        }
+    }
+
+    // the main phase keeps running all along
+    fn end_micro_break(&mut self) {
+        if self.micro_break_deadline.take().is_some() {
+            self.emit(TimerEvent::MicroBreakEnded);
+        }
+    }
+
+    fn skip(&mut self) {
+        if matches!(self.status, AppStatus::Overtime) {
+            // the next phase was announced already
+            self.acknowledge_overtime();
+
+            return;
+        }
+
+        // the session hasn't begun, so there is nothing to skip but the schedule
+        if matches!(self.status, AppStatus::Scheduled) {
+            self.countdown_deadline = None;
+            self.scheduled_at = None;
             self.status = AppStatus::Paused;
+
+            return;
+        }
+
+        let phase = self.get_phase().clone();
+        let next = self.sequence.get(self.next_position()).clone();
+
+        // phases counting up only ever end by hand
+        if phase.is_count_up() {
+            self.record_phase(PhaseOutcome::Completed);
+            self.emit(TimerEvent::PhaseCompleted { phase, next });
+        } else {
+            self.record_phase(PhaseOutcome::Skipped);
+            self.emit(TimerEvent::Skipped { phase, next });
+        }
+
+        self.next_phase();
+    }
+
+    fn acknowledge_overtime(&mut self) {
+        self.record_phase(PhaseOutcome::Completed);
+        self.next_phase();
+    }
+
+    fn next_phase(&mut self) {
+        let counted_up = self
+            .get_phase()
+            .is_count_up()
+            .then(|| self.get_progress_secs());
+
+        self.deadline = None;
+        self.countdown_deadline = None;
+
+        self.set_position(self.next_position());
+
+        // the break earned by a phase counting up depends on how long it lasted
+        if let Some(worked) = counted_up {
+            if !self.get_phase().is_focus() {
+                self.set_progress(self.break_rule.break_for(worked));
+            }
+        }
+
+        self.emit(TimerEvent::PhaseStarted(self.get_phase().clone()));
+        self.auto_start_next();
+    }
+
+    // goes through the phases that ended while the timer was closed, as far as
+    // they would have started on their own, and rewinds the one running now
+    fn catch_up(&mut self, mut passed: Duration) {
+        loop {
+            let (left, deadline) = match self.status {
+                AppStatus::Starting => (
+                    self.countdown_deadline
+                        .as_ref()
+                        .map_or(Duration::ZERO, |countdown_deadline| {
+                            countdown_deadline.remaining(self.clock.as_ref())
+                        }),
+                    &mut self.countdown_deadline,
+                ),
+                // phases ending at a time of day are ahead already
+                AppStatus::Running if self.get_phase().get_until().is_some() => return,
+                AppStatus::Running if self.get_phase().is_count_up() => {
+                    (Duration::MAX, &mut self.deadline)
+                }
+                AppStatus::Running => (Duration::from_secs(self.progress), &mut self.deadline),
+                _ => return,
+            };
+
+            if passed < left {
+                if let Some(deadline) = deadline {
+                    deadline.rewind(passed);
+                }
+
+                return;
+            }
+
+            passed -= left;
+
+            if matches!(self.status, AppStatus::Starting) {
+                self.start();
+
+                continue;
+            }
+
+            if self.overtime {
+                self.deadline = Some(Deadline::before(self.clock.as_ref(), passed));
+                self.status = AppStatus::Overtime;
+
+                return;
+            }
+
+            self.deadline = Some(Deadline::after(self.clock.as_ref(), Duration::ZERO));
+            self.record_phase(PhaseOutcome::Completed);
+            self.next_phase();
         }
     }
 
     fn toggle(&mut self) {
-        if matches!(self.status, AppStatus::Running) {
-            self.status = AppStatus::Paused;
+        if matches!(self.status, AppStatus::Overtime) {
+            self.acknowledge_overtime();
+
+            return;
+        }
+
+        // pausing during the auto-start countdown cancels it
+        if matches!(self.status, AppStatus::Running | AppStatus::Starting) {
+            self.pause();
         } else {
-            self.status = AppStatus::Running;
+            // a phase that never ran is started rather than resumed
+            let resumed = self.phase_started;
+
+            self.start();
+
+            if resumed {
+                self.emit(TimerEvent::Resumed);
+            }
         }
     }
 
+    /// Goes back to the first phase, paused, recording a phase in overtime as
+    /// completed.
     pub fn stop(&mut self) {
+        // the phase was over already, only its overtime is cut short
+        if matches!(self.status, AppStatus::Overtime) {
+            self.record_phase(PhaseOutcome::Completed);
+        }
+
         self.status = AppStatus::Paused;
-        self.is_working = true;
-        self.progress = self.work_duration;
+        self.deadline = None;
+        self.countdown_deadline = None;
+        self.set_position(0);
+    }
+
+    /// Stops the timer and starts the first focus phase the next time the clock
+    /// reads `at`.
+    pub fn schedule(&mut self, at: NaiveTime) {
+        if self.is_locked() {
+            return;
+        }
+
+        self.stop();
+
+        if let Some(position) = (0..self.sequence.len()).find(|&p| self.sequence.get(p).is_focus())
+        {
+            self.set_position(position);
+        }
+
+        let secs = secs_until(at, self.clock.now().time());
+
+        self.countdown_deadline = Some(Deadline::after(
+            self.clock.as_ref(),
+            Duration::from_secs(secs),
+        ));
+        self.scheduled_at = Some(at);
+        self.status = AppStatus::Scheduled;
+    }
+
+    /// The time of day the session is scheduled to start at, while scheduled.
+    pub fn get_scheduled_at(&self) -> Option<NaiveTime> {
+        self.scheduled_at
+            .filter(|_| matches!(self.status, AppStatus::Scheduled))
     }
 
-    pub fn on_tick<F>(&mut self, f: F)
-    where
-        F: FnMut(bool),
-    {
-        self.update_progress(f);
+    /// Catches up with the clock, ending phases, starting the next ones and
+    /// reaching checkpoints as due.
+    pub fn on_tick(&mut self) {
+        self.update_progress();
     }
 
+    /// The events since the last call, for the subscribers.
+    pub fn take_events(&mut self) -> Vec<TimerEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// The progress as shown, e.g. "00:24:59".
     pub fn get_formatted_progress(&self) -> String {
-        let [hours, minutes, seconds] = format_secs(self.progress);
+        let [hours, minutes, seconds] = format_secs(self.get_progress_secs());
 
         format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
     }
 
+    /// Remaining seconds, or elapsed ones for phases counting up or running
+    /// over.
     pub fn get_progress_secs(&self) -> u64 {
-        self.progress
+        if matches!(self.status, AppStatus::Overtime) {
+            self.get_overtime_secs()
+        } else if self.get_phase().is_count_up() {
+            self.elapsed().as_secs()
+        } else {
+            ceil_secs(self.remaining())
+        }
     }
 
-    pub fn get_work_duration(&self) -> u64 {
-        self.work_duration
+    /// Time until the displayed progress or auto-start countdown changes.
+    pub fn get_time_to_next_second(&self) -> Duration {
+        match &self.countdown_deadline {
+            Some(countdown_deadline) => {
+                time_to_next_second(countdown_deadline.remaining(self.clock.as_ref()))
+            }
+            None if self.get_phase().is_count_up()
+                || matches!(self.status, AppStatus::Overtime) =>
+            {
+                let subsec = Duration::from_nanos(self.elapsed().subsec_nanos().into());
+
+                Duration::from_secs(1) - subsec
+            }
+            None => time_to_next_second(self.remaining()),
+        }
     }
 
-    pub fn get_break_duration(&self) -> u64 {
-        self.break_duration
+    /// Seconds into the current phase, extensions included.
+    pub fn get_elapsed_secs(&self) -> u64 {
+        if self.get_phase().is_count_up() {
+            self.elapsed().as_secs()
+        } else {
+            self.phase_duration
+                .saturating_sub(self.get_remaining_secs())
+        }
     }
 
-    pub fn set_work_duration(&mut self, secs: u64) {
-        self.progress = secs;
-        self.work_duration = secs;
+    /// Seconds left of the current phase.
+    pub fn get_remaining_secs(&self) -> u64 {
+        ceil_secs(self.remaining())
     }
 
-    pub fn set_break_duration(&mut self, secs: u64) {
-        self.break_duration = secs;
+    /// How long the finished phase has been running over.
+    pub fn get_overtime_secs(&self) -> u64 {
+        match (&self.status, &self.deadline) {
+            (AppStatus::Overtime, Some(deadline)) => {
+                deadline.elapsed(self.clock.as_ref()).as_secs()
+            }
+            _ => 0,
+        }
     }
 
-    pub fn get_status(&self) -> AppStatus {
-        self.status.clone()
+    /// Whether breaks can be skipped, stopped or paused.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Whether strict mode holds the current break, until it is over.
+    pub fn is_locked(&self) -> bool {
+        self.strict && !self.get_phase().is_focus() && !matches!(self.status, AppStatus::Overtime)
+    }
+
+    /// The way out of a break held by strict mode, meant to be confirmed first.
+    pub fn abort_break(&mut self) {
+        if self.is_locked() {
+            self.skip();
+        }
     }
 
-    pub fn get_is_working(&self) -> bool {
-        self.is_working
+    /// Phases only start on their own within `working_hours`, which stop the
+    /// timer once over.
+    pub fn set_working_hours(&mut self, working_hours: WorkingHours) {
+        self.working_hours = Some(working_hours);
     }
 
-    pub fn set_is_working(&mut self, v: bool) {
-        self.is_working = v;
+    /// Whether the clock is within the working hours, always without any.
+    pub fn is_working_time(&self) -> bool {
+        self.working_hours
+            .as_ref()
+            .is_none_or(|working_hours| working_hours.is_working(self.clock.now()))
+    }
+
+    /// Seconds until the end of the working day, `None` outside working hours
+    /// or without any.
+    pub fn get_end_of_day_secs(&self) -> Option<u64> {
+        self.working_hours
+            .as_ref()
+            .and_then(|working_hours| working_hours.secs_until_end(self.clock.now()))
+    }
 
-        if v {
-            self.progress = self.work_duration;
+    /// Whether the working day ends before the current focus phase, or the next
+    /// one during a break, would.
+    pub fn is_short_on_time(&self) -> bool {
+        let Some(left) = self.get_end_of_day_secs() else {
+            return false;
+        };
+        let len = self.sequence.len();
+        let needed = if self.get_phase().is_focus() {
+            self.get_remaining_secs()
         } else {
-            self.progress = self.break_duration;
+            (1..=len)
+                .map(|i| self.sequence.get((self.position + i) % len))
+                .find(|phase| phase.is_focus())
+                .map_or(0, |phase| phase.get_duration_at(self.clock.now().time()))
+        };
+
+        left < needed
+    }
+
+    /// Nests micro-breaks in focus phases.
+    pub fn set_micro_break(&mut self, micro_break: MicroBreak) {
+        self.micro_break = Some(micro_break);
+    }
+
+    /// Seconds left of the micro-break under way.
+    pub fn get_micro_break_secs(&self) -> Option<u64> {
+        self.micro_break_deadline
+            .as_ref()
+            .map(|deadline| ceil_secs(deadline.remaining(self.clock.as_ref())))
+    }
+
+    /// Alerts reached within each phase.
+    pub fn set_checkpoints(&mut self, checkpoints: Vec<Checkpoint>) {
+        self.checkpoints = checkpoints;
+    }
+
+    /// Whether a finished phase keeps counting until acknowledged, rather than
+    /// moving on to the next one.
+    pub fn set_overtime(&mut self, overtime: bool) {
+        self.overtime = overtime;
+    }
+
+    /// The break the current phase counting up has earned so far.
+    pub fn get_earned_break_secs(&self) -> u64 {
+        self.break_rule.break_for(self.get_progress_secs())
+    }
+
+    /// How long the break after a phase counting up lasts.
+    pub fn set_break_rule(&mut self, break_rule: BreakRule) {
+        self.break_rule = break_rule;
+    }
+
+    /// In seconds, including extensions.
+    pub fn get_phase_duration(&self) -> u64 {
+        self.phase_duration
+    }
+
+    /// The phases ended so far, oldest first.
+    pub fn get_history(&self) -> &[PhaseRecord] {
+        &self.history
+    }
+
+    /// Focus phases completed since midnight.
+    pub fn get_completed_today(&self) -> u64 {
+        let today = self.clock.now().date_naive();
+
+        self.history
+            .iter()
+            .filter(|record| {
+                matches!(record.kind, PhaseKind::Focus)
+                    && matches!(record.outcome, PhaseOutcome::Completed)
+                    && record.ended_at.date_naive() == today
+            })
+            .count() as u64
+    }
+
+    /// Completed focus phases a day, 0 when disabled.
+    pub fn get_daily_goal(&self) -> u64 {
+        self.daily_goal
+    }
+
+    /// Completed focus phases a day, 0 disables it.
+    pub fn set_daily_goal(&mut self, goal: u64) {
+        self.daily_goal = goal;
+    }
+
+    // interruptions are only logged against focus phases and don't pause them
+    fn interrupt(&mut self, kind: InterruptionKind) {
+        if self.get_phase().is_focus() {
+            self.interruptions.push(Interruption {
+                kind,
+                at: self.clock.now(),
+            });
+        }
+    }
+
+    /// Why the current pause was taken.
+    pub fn set_pause_reason(&mut self, reason: PauseReason) {
+        if let Some(pause) = self
+            .pauses
+            .last_mut()
+            .filter(|pause| pause.ended_at.is_none())
+        {
+            pause.reason = Some(reason);
+        }
+    }
+
+    /// What happens to a running focus phase when the terminal loses focus.
+    pub fn set_focus_mode(&mut self, focus_mode: FocusMode) {
+        self.focus_mode = focus_mode;
+    }
+
+    /// Pauses the running focus phase or logs an away interruption, depending
+    /// on the [`FocusMode`].
+    pub fn on_focus_lost(&mut self) {
+        if !self.get_phase().is_focus() || !matches!(self.status, AppStatus::Running) {
+            return;
+        }
+
+        match self.focus_mode {
+            FocusMode::Off => (),
+            FocusMode::Pause => {
+                self.pause();
+                self.set_pause_reason(PauseReason::Away);
+                self.paused_while_away = true;
+            }
+            FocusMode::Interrupt => {
+                self.interrupt(InterruptionKind::Away);
+            }
         }
     }
 
+    /// Whether to offer resuming once the terminal has focus again.
+    pub fn get_paused_while_away(&self) -> bool {
+        self.paused_while_away && matches!(self.status, AppStatus::Paused)
+    }
+
+    /// The time as the timer sees it.
+    pub fn get_now(&self) -> DateTime<Local> {
+        self.clock.now()
+    }
+
+    /// The pauses taken during the current phase.
+    pub fn get_pauses(&self) -> &[Pause] {
+        &self.pauses
+    }
+
+    /// Seconds spent running on focus phases since midnight.
+    pub fn get_focused_today(&self) -> u64 {
+        let today = self.clock.now().date_naive();
+
+        self.history
+            .iter()
+            .filter(|record| {
+                matches!(record.kind, PhaseKind::Focus) && record.ended_at.date_naive() == today
+            })
+            .map(|record| record.active)
+            .sum()
+    }
+
+    /// The interruptions logged during the current phase.
+    pub fn get_interruptions(&self) -> &[Interruption] {
+        &self.interruptions
+    }
+
+    /// All tasks, in the order they were added.
+    pub fn get_tasks(&self) -> &[Task] {
+        self.tasks.get_tasks()
+    }
+
+    /// The task being worked on.
+    pub fn get_active_task(&self) -> Option<&Task> {
+        self.tasks
+            .get_active()
+            .and_then(|index| self.tasks.get(index))
+    }
+
+    /// The index of the task being worked on.
+    pub fn get_active_task_index(&self) -> Option<usize> {
+        self.tasks.get_active()
+    }
+
+    /// Completed focus phases spent on the task at `index`.
+    pub fn get_task_pomodoros(&self, index: usize) -> u64 {
+        self.history
+            .iter()
+            .filter(|record| {
+                record.task == Some(index) && matches!(record.outcome, PhaseOutcome::Completed)
+            })
+            .count() as u64
+    }
+
+    /// Adds a task by name, "Write docs ~3" estimating it at 3 pomodoros.
+    pub fn add_task(&mut self, name: &str) {
+        self.tasks.add(name);
+    }
+
+    /// Makes the task at `index` the one worked on, unless it's done.
+    pub fn select_task(&mut self, index: usize) {
+        self.tasks.select(index);
+    }
+
+    /// Sets the estimate of the task at `index`, in pomodoros.
+    pub fn set_task_estimate(&mut self, index: usize, estimate: Option<u64>) {
+        self.tasks.set_estimate(index, estimate);
+    }
+
+    /// Marks the task at `index` done, or not done anymore.
+    pub fn toggle_task_done(&mut self, index: usize) {
+        self.tasks.toggle_done(index, self.clock.now());
+    }
+
+    /// How many times the current phase was extended.
+    pub fn get_extensions(&self) -> u64 {
+        self.extensions
+    }
+
+    /// How much an extension adds to a phase, in seconds.
+    pub fn set_extend_duration(&mut self, secs: u64) {
+        self.extend_duration = secs;
+    }
+
+    /// How many times a break can be extended.
+    pub fn set_max_break_extensions(&mut self, max: u64) {
+        self.max_break_extensions = max;
+    }
+
+    /// Seconds until the next phase starts on its own, or the scheduled start.
+    pub fn get_countdown_secs(&self) -> u64 {
+        self.countdown_deadline
+            .as_ref()
+            .map_or(0, |countdown_deadline| {
+                ceil_secs(countdown_deadline.remaining(self.clock.as_ref()))
+            })
+    }
+
+    /// Which phases start on their own.
+    pub fn get_auto_start(&self) -> AutoStart {
+        self.auto_start
+    }
+
+    /// Which phases start on their own.
+    pub fn set_auto_start(&mut self, auto_start: AutoStart) {
+        self.auto_start = auto_start;
+    }
+
+    /// Seconds counted down before a phase starts on its own, 0 starting it
+    /// right away.
+    pub fn set_auto_start_countdown(&mut self, secs: u64) {
+        self.auto_start_countdown = secs;
+    }
+
+    /// The current phase.
+    pub fn get_phase(&self) -> &Phase {
+        self.sequence.get(self.position)
+    }
+
+    fn set_position(&mut self, position: usize) {
+        self.end_micro_break();
+        self.position = position;
+        self.interruptions.clear();
+        self.pauses.clear();
+        self.set_progress(self.get_phase().get_duration_at(self.clock.now().time()));
+    }
+
+    /// What's needed to pick the session back up after a restart.
+    pub fn get_state(&self) -> State {
+        State {
+            position: self.position,
+            status: self.status.clone(),
+            progress: self.get_progress_secs(),
+            phase_duration: self.phase_duration,
+            extensions: self.extensions,
+            countdown: self.get_countdown_secs(),
+            saved_at: self.clock.now(),
+            scheduled_at: self.get_scheduled_at(),
+            tasks: self.tasks.clone(),
+            history: self.history.clone(),
+        }
+    }
+
+    /// Picks up where `state` left off. Running phases count the time passed
+    /// since it was saved, going through the phases that would have ended and
+    /// started on their own meanwhile.
+    pub fn restore(&mut self, state: &State) {
+        if state.position >= self.sequence.len() {
+            return;
+        }
+
+        let passed = (self.clock.now() - state.saved_at)
+            .to_std()
+            .unwrap_or(Duration::ZERO);
+
+        self.deadline = None;
+        self.countdown_deadline = None;
+        self.set_position(state.position);
+        self.tasks = state.tasks.clone();
+        self.history = state.history.clone();
+        self.progress = state.progress;
+        self.phase_duration = state.phase_duration;
+        self.extensions = state.extensions;
+        // a paused phase may not have run yet, one counted down to hasn't
+        self.phase_started = match state.status {
+            AppStatus::Paused | AppStatus::Starting => state.progress != state.phase_duration,
+            _ => true,
+        };
+
+        match (&state.status, state.scheduled_at) {
+            (AppStatus::Running, _) => {
+                self.start();
+                self.catch_up(passed);
+            }
+            (AppStatus::Starting, _) => {
+                self.countdown_deadline = Some(Deadline::after(
+                    self.clock.as_ref(),
+                    Duration::from_secs(state.countdown),
+                ));
+                self.status = AppStatus::Starting;
+                self.catch_up(passed);
+            }
+            (AppStatus::Overtime, _) => {
+                let mut deadline =
+                    Deadline::before(self.clock.as_ref(), Duration::from_secs(state.progress));
+                deadline.rewind(passed);
+
+                self.progress = 0;
+                self.deadline = Some(deadline);
+                self.status = AppStatus::Overtime;
+            }
+            (AppStatus::Scheduled, Some(at)) => {
+                let due = secs_until(at, state.saved_at.time());
+
+                self.schedule(at);
+
+                // a start that came while closed happens on the next tick
+                let mut countdown_deadline =
+                    Deadline::after(self.clock.as_ref(), Duration::from_secs(due));
+                countdown_deadline.rewind(passed);
+                self.countdown_deadline = Some(countdown_deadline);
+            }
+            _ => {
+                self.status = AppStatus::Paused;
+            }
+        }
+
+        // checkpoints passed while closed are not worth alerting about anymore
+        self.checked_until = self.get_elapsed_secs();
+    }
+
+    /// Position of the current phase in the sequence, starting at 1.
+    pub fn get_position(&self) -> usize {
+        self.position + 1
+    }
+
+    /// The phases the timer loops over.
+    pub fn get_sequence(&self) -> &Sequence {
+        &self.sequence
+    }
+
+    /// Changes the duration of a phase of the sequence and jumps to it.
+    pub fn set_phase_duration(&mut self, index: usize, secs: u64) {
+        self.sequence.set_duration(index, secs);
+
+        if let Some(position) = self.sequence.position_of(index) {
+            self.set_position(position);
+        }
+    }
+
+    /// Makes a phase of the sequence end at a time of day and jumps to it.
+    pub fn set_phase_until(&mut self, index: usize, until: NaiveTime) {
+        self.sequence.set_until(index, until);
+
+        if let Some(position) = self.sequence.position_of(index) {
+            self.set_position(position);
+        }
+    }
+
+    /// Where the timer is at.
+    pub fn get_status(&self) -> AppStatus {
+        self.status.clone()
+    }
+
+    /// Handles input, strict mode refusing to stop, skip or pause a break.
     pub fn on(&mut self, event: AppEvent) {
+        // a break held by strict mode can only be started or extended
+        if self.is_locked()
+            && match event {
+                AppEvent::Stop | AppEvent::Skip => true,
+                AppEvent::Toggle => {
+                    matches!(self.status, AppStatus::Running | AppStatus::Starting)
+                }
+                _ => false,
+            }
+        {
+            return;
+        }
+
         match event {
             AppEvent::Quit => {
                 self.status = AppStatus::Quit;
@@ -152,8 +1399,628 @@ impl App {
             }
             AppEvent::Stop => {
                 self.stop();
+                self.emit(TimerEvent::Stopped);
+            }
+            AppEvent::CycleAutoStart => {
+                self.auto_start = self.auto_start.next();
+            }
+            AppEvent::Skip => {
+                self.skip();
+            }
+            AppEvent::Extend => {
+                self.extend();
+            }
+            AppEvent::InternalInterruption => {
+                self.interrupt(InterruptionKind::Internal);
+            }
+            AppEvent::ExternalInterruption => {
+                self.interrupt(InterruptionKind::External);
             }
             AppEvent::None => (),
         };
     }
 }
+
+/// Configures an [`App`], e.g.
+/// `App::builder(sequence).overtime(true).daily_goal(8).build()`.
+pub struct AppBuilder {
+    app: App,
+}
+
+impl AppBuilder {
+    /// Which phases start on their own, none by default.
+    pub fn auto_start(mut self, auto_start: AutoStart) -> AppBuilder {
+        self.app.set_auto_start(auto_start);
+        self
+    }
+
+    /// Seconds counted down before a phase starts on its own.
+    pub fn auto_start_countdown(mut self, secs: u64) -> AppBuilder {
+        self.app.set_auto_start_countdown(secs);
+        self
+    }
+
+    /// How much an extension adds to a phase, in seconds.
+    pub fn extend_duration(mut self, secs: u64) -> AppBuilder {
+        self.app.set_extend_duration(secs);
+        self
+    }
+
+    /// How many times a break can be extended.
+    pub fn max_break_extensions(mut self, max: u64) -> AppBuilder {
+        self.app.set_max_break_extensions(max);
+        self
+    }
+
+    /// Phases only start on their own within `working_hours`, which stop the
+    /// timer once over.
+    pub fn working_hours(mut self, working_hours: WorkingHours) -> AppBuilder {
+        self.app.set_working_hours(working_hours);
+        self
+    }
+
+    /// Nests micro-breaks in focus phases.
+    pub fn micro_break(mut self, micro_break: MicroBreak) -> AppBuilder {
+        self.app.set_micro_break(micro_break);
+        self
+    }
+
+    /// Alerts reached within each phase.
+    pub fn checkpoints(mut self, checkpoints: Vec<Checkpoint>) -> AppBuilder {
+        self.app.set_checkpoints(checkpoints);
+        self
+    }
+
+    /// Breaks can't be skipped, stopped or paused.
+    pub fn strict(mut self, strict: bool) -> AppBuilder {
+        self.app.set_strict(strict);
+        self
+    }
+
+    /// A finished phase keeps counting until acknowledged.
+    pub fn overtime(mut self, overtime: bool) -> AppBuilder {
+        self.app.set_overtime(overtime);
+        self
+    }
+
+    /// What happens to a running focus phase when the terminal loses focus.
+    pub fn focus_mode(mut self, focus_mode: FocusMode) -> AppBuilder {
+        self.app.set_focus_mode(focus_mode);
+        self
+    }
+
+    /// Completed focus phases a day, 0 disables it.
+    pub fn daily_goal(mut self, goal: u64) -> AppBuilder {
+        self.app.set_daily_goal(goal);
+        self
+    }
+
+    /// How long the break after a phase counting up lasts.
+    pub fn break_rule(mut self, break_rule: BreakRule) -> AppBuilder {
+        self.app.set_break_rule(break_rule);
+        self
+    }
+
+    /// Where the time is read from, for tests and simulations, see
+    /// [`crate::clock::ManualClock`].
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> AppBuilder {
+        let now = clock.now().time();
+        self.app.clock = clock;
+        self.app
+            .set_progress(self.app.get_phase().get_duration_at(now));
+        self
+    }
+
+    /// Starts the first focus phase at the time of day `at` instead of right
+    /// away.
+    pub fn start_at(mut self, at: NaiveTime) -> AppBuilder {
+        self.app.scheduled_at = Some(at);
+        self
+    }
+
+    /// The configured timer, running unless scheduled or outside working hours.
+    pub fn build(mut self) -> App {
+        match self.app.scheduled_at {
+            Some(at) => self.app.schedule(at),
+            // outside working hours the first phase waits to be started by hand
+            None if !self.app.is_working_time() => (),
+            None => self.app.start(),
+        }
+        self.app
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use chrono::TimeZone;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    // 2024-01-01 was a monday
+    fn on_monday(hour: u32, minute: u32) -> ManualClock {
+        ManualClock::at(
+            Local
+                .with_ymd_and_hms(2024, 1, 1, hour, minute, 0)
+                .unwrap()
+                .into(),
+        )
+    }
+
+    fn builder(spec: &str, clock: &ManualClock) -> AppBuilder {
+        App::builder(Sequence::parse(spec).unwrap()).clock(Arc::new(clock.clone()))
+    }
+
+    fn names(events: &[TimerEvent]) -> Vec<&'static str> {
+        events
+            .iter()
+            .map(|event| event.name())
+            .filter(|name| *name != "tick")
+            .collect()
+    }
+
+    fn auto_started(clock: &ManualClock) -> App {
+        builder("focus 25m, rest 5m", clock)
+            .auto_start(AutoStart::Both)
+            .auto_start_countdown(3)
+            .build()
+    }
+
+    #[test]
+    fn restores_through_the_phases_ended_while_closed() {
+        let clock = ManualClock::new();
+        let mut app = auto_started(&clock);
+
+        clock.advance(MINUTE);
+        let state = app.get_state();
+
+        // the rest of the focus phase, a countdown, the break, a countdown and 2 minutes
+        clock.advance(24 * MINUTE + 5 * MINUTE + 2 * MINUTE + Duration::from_secs(6));
+        app = auto_started(&clock);
+        app.take_events();
+        app.restore(&state);
+        assert_eq!(app.get_history().len(), 2);
+        assert_eq!(app.get_position(), 1);
+        assert_eq!(app.get_status(), AppStatus::Running);
+        assert_eq!(app.get_remaining_secs(), 23 * 60);
+        assert_eq!(
+            names(&app.take_events()),
+            [
+                "phase-started",
+                "phase-running",
+                "phase-started",
+                "phase-running"
+            ]
+        );
+    }
+
+    #[test]
+    fn restores_during_an_auto_start_countdown() {
+        let clock = ManualClock::new();
+        let mut app = auto_started(&clock);
+
+        clock.advance(25 * MINUTE);
+        app.on_tick();
+        clock.advance(Duration::from_secs(1));
+        let state = app.get_state();
+
+        assert_eq!(state.status, AppStatus::Starting);
+        assert_eq!(state.countdown, 2);
+
+        clock.advance(Duration::from_secs(1));
+        app = auto_started(&clock);
+        app.restore(&state);
+        assert_eq!(app.get_status(), AppStatus::Starting);
+        assert_eq!(app.get_countdown_secs(), 1);
+
+        // the countdown ran out while closed
+        clock.advance(2 * MINUTE + Duration::from_secs(1));
+        app = auto_started(&clock);
+        app.take_events();
+        app.restore(&state);
+        assert_eq!(app.get_position(), 2);
+        assert_eq!(app.get_status(), AppStatus::Running);
+        assert_eq!(app.get_remaining_secs(), 3 * 60);
+        assert_eq!(names(&app.take_events()), ["phase-running"]);
+    }
+
+    #[test]
+    fn restores_after_an_auto_start_countdown() {
+        let clock = ManualClock::new();
+        let mut app = auto_started(&clock);
+
+        clock.advance(25 * MINUTE);
+        app.on_tick();
+        clock.advance(Duration::from_secs(3));
+        app.on_tick();
+        assert!(names(&app.take_events()).ends_with(&["phase-running"]));
+
+        clock.advance(2 * MINUTE);
+        let state = app.get_state();
+
+        app = auto_started(&clock);
+        app.restore(&state);
+        assert_eq!(app.get_position(), 2);
+        assert_eq!(app.get_status(), AppStatus::Running);
+        assert_eq!(app.get_remaining_secs(), 3 * 60);
+    }
+
+    #[test]
+    fn runs_through_phases_for_hours() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus 25m, rest 5m", &clock)
+            .auto_start(AutoStart::Both)
+            .auto_start_countdown(0)
+            .build();
+        let mut events = Vec::new();
+
+        for _ in 0..180 {
+            clock.advance(MINUTE);
+            app.on_tick();
+            events.extend(app.take_events());
+        }
+
+        assert_eq!(
+            names(&events)[..4],
+            [
+                "phase-running",
+                "phase-completed",
+                "phase-started",
+                "phase-running"
+            ]
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, TimerEvent::PhaseCompleted { .. }))
+                .count(),
+            12
+        );
+        assert_eq!(
+            events.last(),
+            Some(&TimerEvent::PhaseRunning(app.get_phase().clone()))
+        );
+        assert!(app.take_events().is_empty());
+        assert_eq!(app.get_history().len(), 12);
+        assert_eq!(app.get_history()[0].active, 25 * 60);
+        assert_eq!(app.get_history()[1].active, 5 * 60);
+        assert_eq!(app.get_position(), 1);
+        assert_eq!(app.get_status(), AppStatus::Running);
+        assert_eq!(app.get_remaining_secs(), 25 * 60);
+    }
+
+    #[test]
+    fn reaches_checkpoints_while_running() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus 25m, rest 5m", &clock)
+            .checkpoints(vec![
+                Checkpoint::parse("2m left; Wrap up").unwrap(),
+                Checkpoint::parse("every 10m").unwrap(),
+            ])
+            .build();
+        let mut reached = Vec::new();
+
+        for _ in 0..30 {
+            clock.advance(MINUTE);
+            app.on_tick();
+
+            for event in app.take_events() {
+                if let TimerEvent::Checkpoint(checkpoint) = event {
+                    reached.push(checkpoint.get_message().to_string());
+                }
+            }
+        }
+
+        assert_eq!(reached, ["Another 10m", "Another 10m", "Wrap up"]);
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_working_day() {
+        let clock = on_monday(17, 0);
+        let mut app = builder("focus 60m, rest 5m", &clock)
+            .working_hours(WorkingHours::parse("mon-fri 09:00-17:30").unwrap())
+            .build();
+
+        assert_eq!(app.get_end_of_day_secs(), Some(30 * 60));
+        assert!(app.is_short_on_time());
+        app.take_events();
+
+        for _ in 0..30 {
+            clock.advance(MINUTE);
+            app.on_tick();
+        }
+
+        assert_eq!(names(&app.take_events()), ["paused", "end-of-day"]);
+        assert_eq!(app.get_status(), AppStatus::Paused);
+        assert_eq!(app.get_remaining_secs(), 30 * 60);
+    }
+
+    #[test]
+    fn starts_nothing_on_its_own_outside_working_hours() {
+        let clock = on_monday(18, 0);
+        let mut app = builder("focus 5m, rest 5m", &clock)
+            .working_hours(WorkingHours::parse("mon-fri 09:00-17:30").unwrap())
+            .auto_start(AutoStart::Both)
+            .auto_start_countdown(0)
+            .build();
+
+        assert_eq!(app.get_status(), AppStatus::Paused);
+        assert!(app.take_events().is_empty());
+
+        // started by hand, the next phase waits all the same
+        app.on(AppEvent::Toggle);
+        clock.advance(5 * MINUTE);
+        app.on_tick();
+        assert_eq!(app.get_phase().get_name(), "Rest");
+        assert_eq!(app.get_status(), AppStatus::Paused);
+    }
+
+    #[test]
+    fn takes_micro_breaks_while_focusing() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus 60m, rest 5m", &clock)
+            .micro_break(MicroBreak::new(20 * 60, 20))
+            .build();
+        app.take_events();
+
+        clock.advance(20 * MINUTE);
+        app.on_tick();
+        assert_eq!(
+            app.take_events()
+                .into_iter()
+                .filter(|event| !matches!(event, TimerEvent::Tick { .. }))
+                .collect::<Vec<_>>(),
+            [TimerEvent::MicroBreakStarted { secs: 20 }]
+        );
+
+        clock.advance(Duration::from_secs(20));
+        app.on_tick();
+        assert_eq!(names(&app.take_events()), ["micro-break-ended"]);
+        assert_eq!(app.get_status(), AppStatus::Running);
+    }
+
+    #[test]
+    fn takes_no_micro_breaks_without_an_interval() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus 60m, rest 5m", &clock)
+            .micro_break(MicroBreak::new(0, 20))
+            .build();
+        app.take_events();
+
+        for _ in 0..30 {
+            clock.advance(MINUTE);
+            app.on_tick();
+        }
+        assert!(names(&app.take_events()).is_empty());
+    }
+
+    #[test]
+    fn earns_a_break_by_focusing_in_flowtime() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus open, rest 5m", &clock)
+            .break_rule(BreakRule::Fraction(5))
+            .build();
+
+        for _ in 0..50 {
+            clock.advance(MINUTE);
+            app.on_tick();
+        }
+        assert_eq!(app.get_progress_secs(), 50 * 60);
+        assert_eq!(app.get_earned_break_secs(), 10 * 60);
+        app.take_events();
+
+        // ending a phase counting up completes it
+        app.on(AppEvent::Skip);
+        assert_eq!(
+            names(&app.take_events())[..2],
+            ["phase-completed", "phase-started"]
+        );
+        assert_eq!(app.get_history()[0].outcome, PhaseOutcome::Completed);
+        assert_eq!(app.get_phase().get_kind(), PhaseKind::Rest);
+        assert_eq!(app.get_remaining_secs(), 10 * 60);
+    }
+
+    #[test]
+    fn ends_phases_at_a_time_of_day() {
+        let clock = on_monday(9, 0);
+        let mut app = builder("focus @10:00, rest 5m", &clock).build();
+
+        assert_eq!(app.get_remaining_secs(), 60 * 60);
+
+        for _ in 0..59 {
+            clock.advance(MINUTE);
+            app.on_tick();
+        }
+        assert_eq!(app.get_phase().get_kind(), PhaseKind::Focus);
+        assert_eq!(app.get_remaining_secs(), 60);
+
+        clock.advance(MINUTE);
+        app.on_tick();
+        assert_eq!(app.get_phase().get_kind(), PhaseKind::Rest);
+        assert_eq!(app.get_history()[0].outcome, PhaseOutcome::Completed);
+    }
+
+    #[test]
+    fn counts_the_time_suspended() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus 25m, rest 5m", &clock).build();
+
+        clock.advance(10 * MINUTE);
+        clock.suspend(10 * MINUTE);
+        app.on_tick();
+        assert_eq!(app.get_remaining_secs(), 5 * 60);
+        app.take_events();
+
+        // the phase ended while suspended
+        clock.suspend(10 * MINUTE);
+        app.on_tick();
+        assert_eq!(
+            names(&app.take_events()),
+            ["phase-completed", "phase-started"]
+        );
+        assert_eq!(app.get_phase().get_name(), "Rest");
+        assert_eq!(app.get_status(), AppStatus::Paused);
+    }
+
+    #[test]
+    fn runs_over_until_acknowledged() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus 25m, rest 5m", &clock).overtime(true).build();
+
+        clock.advance(27 * MINUTE);
+        app.on_tick();
+        assert_eq!(app.get_status(), AppStatus::Overtime);
+        assert_eq!(app.get_overtime_secs(), 2 * 60);
+        assert!(app.get_history().is_empty());
+
+        app.on(AppEvent::Toggle);
+        assert_eq!(app.get_phase().get_name(), "Rest");
+        assert_eq!(app.get_history()[0].overrun, 2 * 60);
+        assert_eq!(app.get_history()[0].active, 27 * 60);
+    }
+
+    #[test]
+    fn stopping_in_overtime_records_the_phase() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus 25m, rest 5m", &clock).overtime(true).build();
+
+        clock.advance(26 * MINUTE);
+        app.on_tick();
+        app.on(AppEvent::Stop);
+        assert_eq!(app.get_history().len(), 1);
+        assert_eq!(app.get_history()[0].outcome, PhaseOutcome::Completed);
+        assert_eq!(app.get_position(), 1);
+        assert_eq!(app.get_status(), AppStatus::Paused);
+    }
+
+    #[test]
+    fn skips_to_the_next_phase() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus 25m, rest 5m", &clock).build();
+        app.take_events();
+
+        clock.advance(10 * MINUTE);
+        app.on(AppEvent::Skip);
+        assert_eq!(names(&app.take_events()), ["skipped", "phase-started"]);
+        assert_eq!(app.get_history()[0].outcome, PhaseOutcome::Skipped);
+        assert_eq!(app.get_history()[0].active, 10 * 60);
+        assert_eq!(app.get_phase().get_name(), "Rest");
+        assert_eq!(app.get_status(), AppStatus::Paused);
+
+        // the skipped to phase hasn't run yet
+        app.on(AppEvent::Toggle);
+        assert_eq!(names(&app.take_events()), ["phase-running"]);
+    }
+
+    #[test]
+    fn extends_breaks_up_to_the_limit() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus 25m, rest 5m", &clock)
+            .extend_duration(5 * 60)
+            .max_break_extensions(1)
+            .build();
+
+        app.on(AppEvent::Extend);
+        app.on(AppEvent::Extend);
+        assert_eq!(app.get_remaining_secs(), 35 * 60);
+
+        app.on(AppEvent::Skip);
+        app.on(AppEvent::Extend);
+        app.on(AppEvent::Extend);
+        assert_eq!(app.get_extensions(), 1);
+        assert_eq!(app.get_remaining_secs(), 10 * 60);
+    }
+
+    #[test]
+    fn strict_mode_holds_breaks() {
+        let clock = ManualClock::new();
+        let mut app = builder("focus 25m, rest 5m", &clock)
+            .strict(true)
+            .auto_start(AutoStart::Both)
+            .auto_start_countdown(0)
+            .build();
+
+        // focus phases are left alone
+        app.on(AppEvent::Toggle);
+        assert_eq!(app.get_status(), AppStatus::Paused);
+        app.on(AppEvent::Toggle);
+
+        clock.advance(25 * MINUTE);
+        app.on_tick();
+        app.take_events();
+        assert!(app.is_locked());
+
+        for event in [AppEvent::Skip, AppEvent::Stop, AppEvent::Toggle] {
+            app.on(event);
+        }
+        assert!(app.take_events().is_empty());
+        assert_eq!(app.get_phase().get_name(), "Rest");
+        assert_eq!(app.get_status(), AppStatus::Running);
+
+        app.abort_break();
+        assert_eq!(app.get_phase().get_name(), "Focus");
+        assert!(!app.is_locked());
+    }
+
+    #[test]
+    fn starts_at_the_scheduled_time() {
+        let clock = on_monday(8, 30);
+        let at = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        let mut app = builder("focus 25m, rest 5m", &clock).start_at(at).build();
+
+        assert_eq!(app.get_status(), AppStatus::Scheduled);
+        assert_eq!(app.get_scheduled_at(), Some(at));
+        assert!(app.take_events().is_empty());
+
+        clock.advance(30 * MINUTE);
+        app.on_tick();
+        assert_eq!(
+            names(&app.take_events()),
+            ["phase-running", "scheduled-start"]
+        );
+        assert_eq!(app.get_status(), AppStatus::Running);
+        assert_eq!(app.get_scheduled_at(), None);
+    }
+
+    #[test]
+    fn skipping_cancels_the_schedule() {
+        let clock = on_monday(8, 30);
+        let at = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        let mut app = builder("focus 25m, rest 5m", &clock).start_at(at).build();
+
+        app.on(AppEvent::Skip);
+        assert!(app.take_events().is_empty());
+        assert!(app.get_history().is_empty());
+        assert_eq!(app.get_status(), AppStatus::Paused);
+        assert_eq!(app.get_phase().get_name(), "Focus");
+
+        clock.advance(30 * MINUTE);
+        app.on_tick();
+        assert_eq!(app.get_status(), AppStatus::Paused);
+    }
+
+    #[test]
+    fn reaches_the_daily_goal_once() {
+        let clock = on_monday(9, 0);
+        let mut app = builder("focus 25m, rest 5m", &clock)
+            .auto_start(AutoStart::Both)
+            .auto_start_countdown(0)
+            .daily_goal(2)
+            .build();
+        let mut reached = Vec::new();
+
+        for _ in 0..120 {
+            clock.advance(MINUTE);
+            app.on_tick();
+            reached.extend(
+                app.take_events()
+                    .into_iter()
+                    .filter(|event| matches!(event, TimerEvent::GoalReached { .. })),
+            );
+        }
+
+        assert_eq!(reached, [TimerEvent::GoalReached { completed: 2 }]);
+        assert_eq!(app.get_completed_today(), 4);
+    }
+}
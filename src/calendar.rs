@@ -0,0 +1,126 @@
+//! Working hours over the week.
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, Weekday};
+
+use crate::utils::MyResult;
+
+/// The hours worked on each day of the week, days off having none.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkingHours {
+    days: [Option<(NaiveTime, NaiveTime)>; 7], // (start, end), from monday
+}
+
+impl WorkingHours {
+    /// Parses comma separated days or ranges of days with their hours, e.g.
+    /// "mon-fri 09:00-17:30, sat 10:00-12:00".
+    pub fn parse(spec: &str) -> MyResult<WorkingHours> {
+        let mut working_hours = WorkingHours { days: [None; 7] };
+
+        for item in spec.split(',') {
+            let invalid = || format!("invalid working hours `{}`", item.trim());
+            let (days, hours) = item.trim().split_once(' ').ok_or_else(invalid)?;
+            let (first, last) = days.split_once('-').unwrap_or((days, days));
+            let first = first.parse::<Weekday>().map_err(|_| invalid())?;
+            let last = last.parse::<Weekday>().map_err(|_| invalid())?;
+            let (start, end) = hours
+                .trim()
+                .split_once('-')
+                .and_then(|(start, end)| {
+                    Some((
+                        NaiveTime::parse_from_str(start, "%H:%M").ok()?,
+                        NaiveTime::parse_from_str(end, "%H:%M").ok()?,
+                    ))
+                })
+                .filter(|(start, end)| start < end)
+                .ok_or_else(invalid)?;
+
+            // ranges may wrap around the week, e.g. "sun-thu"
+            let mut day = first;
+            loop {
+                working_hours.days[day.num_days_from_monday() as usize] = Some((start, end));
+
+                if day == last {
+                    break;
+                }
+                day = day.succ();
+            }
+        }
+
+        Ok(working_hours)
+    }
+
+    /// The start and end of work on `weekday`, if any.
+    pub fn get_hours(&self, weekday: Weekday) -> Option<(NaiveTime, NaiveTime)> {
+        self.days[weekday.num_days_from_monday() as usize]
+    }
+
+    /// Whether `now` is within the working hours.
+    pub fn is_working(&self, now: DateTime<Local>) -> bool {
+        self.secs_until_end(now).is_some()
+    }
+
+    /// Seconds until the end of the working day, `None` outside working hours.
+    pub fn secs_until_end(&self, now: DateTime<Local>) -> Option<u64> {
+        let (start, end) = self.get_hours(now.weekday())?;
+        let time = now.time();
+
+        (start <= time && time < end).then(|| (end - time).num_seconds().max(0) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_working_hours() {
+        let working_hours = WorkingHours::parse("mon-fri 09:00-17:30, sat 10:00-12:00").unwrap();
+
+        assert_eq!(
+            working_hours.get_hours(Weekday::Wed),
+            Some((time(9, 0), time(17, 30)))
+        );
+        assert_eq!(
+            working_hours.get_hours(Weekday::Sat),
+            Some((time(10, 0), time(12, 0)))
+        );
+        assert_eq!(working_hours.get_hours(Weekday::Sun), None);
+
+        // ranges wrap around the week
+        let working_hours = WorkingHours::parse("sun-tue 08:00-12:00").unwrap();
+
+        assert!(working_hours.get_hours(Weekday::Sun).is_some());
+        assert!(working_hours.get_hours(Weekday::Tue).is_some());
+        assert!(working_hours.get_hours(Weekday::Wed).is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_working_hours() {
+        for spec in [
+            "",
+            "mon",
+            "funday 09:00-17:00",
+            "mon 17:00-09:00",
+            "mon 9h-17h",
+        ] {
+            assert!(WorkingHours::parse(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn counts_down_to_the_end_of_the_day() {
+        let working_hours = WorkingHours::parse("mon-fri 09:00-17:30").unwrap();
+        // 2024-01-01 was a monday
+        let monday = |hour, minute| Local.with_ymd_and_hms(2024, 1, 1, hour, minute, 0).unwrap();
+
+        assert_eq!(working_hours.secs_until_end(monday(17, 0)), Some(30 * 60));
+        assert!(working_hours.is_working(monday(9, 0)));
+        assert!(!working_hours.is_working(monday(8, 59)));
+        assert!(!working_hours.is_working(monday(17, 30)));
+    }
+}
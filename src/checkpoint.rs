@@ -0,0 +1,157 @@
+//! Alerts within a phase.
+
+use crate::phase::parse_duration;
+use crate::utils::MyResult;
+
+/// When a [`Checkpoint`] is reached.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheckpointAt {
+    /// Seconds before the end of the phase.
+    Remaining(u64),
+    /// Percentage of the phase's duration elapsed.
+    Percent(u64),
+    /// Seconds, all along the phase.
+    Every(u64),
+}
+
+/// An alert within a phase, e.g. "2 minutes left" or a chime every 15 minutes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Checkpoint {
+    at: CheckpointAt,
+    message: String,
+    sound: Option<String>, // left to the subscriber to make sense of
+}
+
+impl Checkpoint {
+    /// A checkpoint with its message and, optionally, the sound to play.
+    pub fn new(at: CheckpointAt, message: &str, sound: Option<&str>) -> Checkpoint {
+        Checkpoint {
+            at,
+            message: message.to_string(),
+            sound: sound.map(|sound| sound.to_string()),
+        }
+    }
+
+    /// Parses `when; message; sound`, the last two optional, when being
+    /// "2m left", "halfway", "25%" or "every 15m".
+    pub fn parse(spec: &str) -> MyResult<Checkpoint> {
+        let mut fields = spec.split(';').map(|field| field.trim());
+        let when = fields.next().unwrap_or_default();
+        let invalid = || format!("invalid checkpoint `{}`", when);
+
+        let (at, default_message) = if when == "halfway" {
+            (CheckpointAt::Percent(50), "Halfway".to_string())
+        } else if let Some(percent) = when.strip_suffix('%') {
+            let percent = percent.trim().parse().map_err(|_| invalid())?;
+
+            (CheckpointAt::Percent(percent), format!("{}% done", percent))
+        } else if let Some(every) = when.strip_prefix("every ") {
+            let secs = parse_duration(every.trim()).ok_or_else(invalid)?;
+
+            (
+                CheckpointAt::Every(secs),
+                format!("Another {}", every.trim()),
+            )
+        } else if let Some(remaining) = when.strip_suffix(" left") {
+            let secs = parse_duration(remaining.trim()).ok_or_else(invalid)?;
+
+            (
+                CheckpointAt::Remaining(secs),
+                format!("{} left", remaining.trim()),
+            )
+        } else {
+            return Err(invalid().into());
+        };
+
+        let message = fields
+            .next()
+            .filter(|message| !message.is_empty())
+            .map_or(default_message, |message| message.to_string());
+        let sound = fields.next().filter(|sound| !sound.is_empty());
+
+        Ok(Checkpoint::new(at, &message, sound))
+    }
+
+    /// What to alert with.
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    /// The sound to play, left to the subscriber to make sense of.
+    pub fn get_sound(&self) -> Option<&str> {
+        self.sound.as_deref()
+    }
+
+    /// Whether the phase went past it between `from` and `to` seconds in,
+    /// `duration` being unknown for phases counting up.
+    pub fn is_crossed(&self, from: u64, to: u64, duration: Option<u64>) -> bool {
+        let target = match (&self.at, duration) {
+            (CheckpointAt::Every(secs), _) => {
+                // the last one would coincide with the end of the phase
+                return *secs > 0
+                    && to / secs > from / secs
+                    && duration.is_none_or(|duration| to < duration);
+            }
+            (CheckpointAt::Remaining(secs), Some(duration)) if *secs < duration => duration - secs,
+            (CheckpointAt::Percent(percent), Some(duration)) if *percent < 100 => {
+                duration * percent / 100
+            }
+            _ => return false,
+        };
+
+        from < target && target <= to
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_checkpoints() {
+        let checkpoint = Checkpoint::parse("2m left; Wrap up").unwrap();
+
+        assert_eq!(checkpoint.at, CheckpointAt::Remaining(2 * 60));
+        assert_eq!(checkpoint.get_message(), "Wrap up");
+        assert_eq!(checkpoint.get_sound(), None);
+
+        let checkpoint = Checkpoint::parse("every 15m; Stretch; chime.ogg").unwrap();
+
+        assert_eq!(checkpoint.at, CheckpointAt::Every(15 * 60));
+        assert_eq!(checkpoint.get_sound(), Some("chime.ogg"));
+
+        assert_eq!(
+            Checkpoint::parse("halfway").unwrap(),
+            Checkpoint::new(CheckpointAt::Percent(50), "Halfway", None)
+        );
+        assert_eq!(
+            Checkpoint::parse("25%;;").unwrap(),
+            Checkpoint::new(CheckpointAt::Percent(25), "25% done", None)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_checkpoints() {
+        for spec in ["", "soon", "every x", "x%", "2q left; Wrap up"] {
+            assert!(Checkpoint::parse(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn is_crossed_once() {
+        let checkpoint = Checkpoint::parse("2m left").unwrap();
+
+        assert!(!checkpoint.is_crossed(0, 22 * 60, Some(25 * 60)));
+        assert!(checkpoint.is_crossed(22 * 60, 23 * 60, Some(25 * 60)));
+        assert!(!checkpoint.is_crossed(23 * 60, 24 * 60, Some(25 * 60)));
+        // phases counting up have no end to count from
+        assert!(!checkpoint.is_crossed(0, 60 * 60, None));
+
+        let checkpoint = Checkpoint::parse("every 10m").unwrap();
+
+        assert!(checkpoint.is_crossed(9 * 60, 11 * 60, None));
+        assert!(!checkpoint.is_crossed(11 * 60, 19 * 60, None));
+        // not at the end of the phase
+        assert!(!checkpoint.is_crossed(19 * 60, 20 * 60, Some(20 * 60)));
+    }
+}
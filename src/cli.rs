@@ -0,0 +1,240 @@
+use crate::app::{App, AppStatus, AutoStart, FocusMode};
+use crate::calendar::WorkingHours;
+use crate::checkpoint::Checkpoint;
+use crate::events::{EventBus, TimerEvent};
+use crate::micro_break::MicroBreak;
+use crate::phase::{parse_duration, BreakRule, Phase, PhaseColor, PhaseKind, Sequence};
+use crate::state::State;
+use crate::subscribers::{HistoryLog, Hook};
+use crate::timers::Timers;
+use crate::ui::Ui;
+use crate::utils::{format_secs, MyResult, SECONDS_IN_HOURS, SECONDS_IN_MINUTES};
+use chrono::{NaiveTime, Timelike};
+use crossterm::event::poll;
+use std::{env, io};
+use tui::{backend::CrosstermBackend, Terminal};
+
+use crate::notification::Notification;
+
+// e.g. `--sequence "focus 90m, rest 20m"`
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = env::args().skip_while(|arg| arg != name);
+
+    args.nth(1)
+}
+
+// every value of a repeatable argument
+fn arg_values(name: &str) -> Vec<String> {
+    let args: Vec<String> = env::args().collect();
+
+    args.windows(2)
+        .filter(|pair| pair[0] == name)
+        .map(|pair| pair[1].clone())
+        .collect()
+}
+
+fn has_flag(name: &str) -> bool {
+    env::args().any(|arg| arg == name)
+}
+
+/// Runs the terminal app, configured from the command line arguments, until
+/// quit.
+pub fn start() -> MyResult<()> {
+    let stdout = io::stdout();
+    let sequence = match arg_value("--sequence") {
+        Some(spec) => Sequence::parse(&spec)?,
+        // count up while working, then take a break proportional to it
+        None if has_flag("--flowtime") => Sequence::parse("focus open Flow, rest 0s Break")?,
+        None => Sequence::classic(
+            Phase::new(
+                "Work time",
+                1 * SECONDS_IN_HOURS,
+                PhaseKind::Focus,
+                PhaseColor::Yellow,
+            ),
+            Phase::new(
+                "Break time",
+                15 * SECONDS_IN_MINUTES,
+                PhaseKind::Rest,
+                PhaseColor::Green,
+            ),
+            Phase::new(
+                "Long break time",
+                30 * SECONDS_IN_MINUTES,
+                PhaseKind::Rest,
+                PhaseColor::Cyan,
+            ),
+            // e.g. `--long-break-every 3`
+            match arg_value("--long-break-every") {
+                Some(interval) => interval.parse()?,
+                None => 4,
+            },
+        ),
+    };
+    let mut builder = App::builder(sequence);
+    // e.g. `--start-at 09:00`
+    if let Some(at) = arg_value("--start-at") {
+        builder = builder.start_at(
+            NaiveTime::parse_from_str(&at, "%H:%M")
+                .map_err(|_| format!("invalid time of day `{}`", at))?,
+        );
+    }
+    // e.g. `--hours "mon-fri 09:00-17:30"`
+    if let Some(spec) = arg_value("--hours") {
+        builder = builder.working_hours(WorkingHours::parse(&spec)?);
+    }
+    // e.g. `--micro-breaks 20m:20s`, looking away for 20 seconds every 20 minutes
+    if let Some(spec) = arg_value("--micro-breaks") {
+        builder = builder.micro_break(MicroBreak::parse(&spec)?);
+    }
+    let app = builder
+        // e.g. `--auto-start breaks`, one of off, breaks, work or all
+        .auto_start(match arg_value("--auto-start") {
+            Some(label) => AutoStart::parse(&label)?,
+            None => AutoStart::Neither,
+        })
+        // in seconds, before a phase starts on its own
+        .auto_start_countdown(match arg_value("--auto-start-countdown") {
+            Some(secs) => secs.parse()?,
+            None => 3,
+        })
+        // e.g. `--extend 10m`
+        .extend_duration(match arg_value("--extend") {
+            Some(duration) => parse_duration(&duration)
+                .ok_or_else(|| format!("invalid duration `{}`", duration))?,
+            None => 5 * SECONDS_IN_MINUTES,
+        })
+        .max_break_extensions(match arg_value("--max-break-extensions") {
+            Some(max) => max.parse()?,
+            None => 1,
+        })
+        .overtime(has_flag("--overtime"))
+        .strict(has_flag("--strict"))
+        // e.g. `--checkpoint "2m left; Wrap up"` or `--checkpoint "every 15m; Stretch; chime.ogg"`
+        .checkpoints(
+            arg_values("--checkpoint")
+                .iter()
+                .map(|spec| Checkpoint::parse(spec))
+                .collect::<MyResult<_>>()?,
+        )
+        .focus_mode(if has_flag("--focus-pause") {
+            FocusMode::Pause
+        } else if has_flag("--focus-interrupt") {
+            FocusMode::Interrupt
+        } else {
+            FocusMode::Off
+        })
+        .daily_goal(match arg_value("--goal") {
+            Some(goal) => goal.parse()?,
+            None => 8,
+        })
+        .break_rule(match arg_value("--flowtime-breaks") {
+            Some(spec) => BreakRule::parse(&spec)?,
+            None => BreakRule::Fraction(5),
+        })
+        .build();
+    // e.g. `--timer "Laundry: rest 45m"`, for as many timers as needed
+    let other_timers = arg_values("--timer")
+        .iter()
+        .map(|timer| {
+            let (name, spec) = timer
+                .split_once(':')
+                .ok_or_else(|| format!("expected `name: sequence` in `{}`", timer))?;
+
+            Ok((name.trim().to_string(), App::new(Sequence::parse(spec)?)))
+        })
+        .collect::<MyResult<Vec<_>>>()?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+    // the editable durations, the first three phases of the sequence
+    let phases = app.get_sequence().get_phases();
+    let inputs = [0, 1, 2].map(|i| {
+        let secs = match phases.get(i) {
+            Some(phase) => match phase.get_until() {
+                Some(until) => until.num_seconds_from_midnight().into(),
+                None => phase.get_duration(),
+            },
+            None => 0,
+        };
+
+        format_secs(secs).map(|e| e.to_string())
+    });
+    let until_inputs = [0, 1, 2].map(|i| {
+        phases
+            .get(i)
+            .is_some_and(|phase| phase.get_until().is_some())
+    });
+    let mut ui = Ui::new(
+        "Pomodoro",
+        ["Hours", "Minutes", "Seconds"],
+        inputs,
+        until_inputs,
+    );
+    ui.setup_terminal()?;
+
+    let mut events = EventBus::new();
+    events.subscribe(Box::new(Notification::new()));
+    if let Some(path) = arg_value("--log") {
+        events.subscribe(Box::new(HistoryLog::new(&path)));
+    }
+    if let Some(command) = arg_value("--hook") {
+        events.subscribe(Box::new(Hook::new(&command)));
+    }
+
+    // `--state none` disables saving the session
+    let state_path = arg_value("--state")
+        .or_else(|| {
+            env::var("HOME")
+                .ok()
+                .map(|home| format!("{}/.pomodoro-state", home))
+        })
+        .filter(|path| path != "none");
+    if let Some(state) = state_path
+        .as_deref()
+        .and_then(|path| State::load(path).ok())
+    {
+        ui.offer_restore(state);
+    }
+
+    let mut timers = Timers::new("Pomodoro", app);
+    for (name, app) in other_timers {
+        timers.add(&name, app);
+    }
+
+    loop {
+        ui.draw(&mut terminal, &mut timers)?;
+
+        // the remaining time is derived from the phase deadlines, so there is no
+        // tick to keep up with, just redraw whenever a displayed second changes,
+        // or every second while paused to keep the paused time up to date
+        let mut changed = false;
+
+        if poll(timers.get_time_to_next_second())? {
+            changed = ui.handle_keypress(&mut timers)?;
+        };
+
+        for timer in timers.get_timers_mut() {
+            timer.app.on_tick();
+            let new_events = timer.app.take_events();
+            changed |= new_events
+                .iter()
+                .any(|event| !matches!(event, TimerEvent::Tick { .. }));
+            events.publish(&timer.name, new_events);
+        }
+
+        if let AppStatus::Quit = timers.get_focused().app.get_status() {
+            ui.cleanup()?;
+
+            return Ok(());
+        }
+
+        // saving the main timer is best effort, and waits for the previous
+        // session to be restored or dismissed so it isn't overwritten
+        if let Some(path) = state_path
+            .as_deref()
+            .filter(|_| changed && !ui.is_restore_offered())
+        {
+            let _ = timers.get_timers()[0].app.get_state().save(path);
+        }
+    }
+}
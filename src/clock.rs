@@ -0,0 +1,136 @@
+//! The time source of the timer.
+
+use chrono::{DateTime, Local};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Where the timer reads the time from.
+///
+/// The monotonic and wall clock times are read separately as they drift apart
+/// while the machine is suspended.
+pub trait Clock: Send + Sync {
+    /// The monotonic time, which stands still while the machine is suspended.
+    fn instant(&self) -> Instant;
+
+    /// The wall clock time.
+    fn system_time(&self) -> SystemTime;
+
+    /// The wall clock time in the local time zone.
+    fn now(&self) -> DateTime<Local> {
+        self.system_time().into()
+    }
+}
+
+/// The actual time, the default.
+#[derive(Clone, Copy, Debug)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time, so one
+/// can be handed to the timer while the other fast-forwards it:
+///
+/// ```
+/// use pomodoro::app::App;
+/// use pomodoro::clock::ManualClock;
+/// use pomodoro::phase::Sequence;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::new();
+/// let sequence = Sequence::parse("focus 25m, rest 5m").unwrap();
+/// let mut app = App::builder(sequence).clock(Arc::new(clock.clone())).build();
+///
+/// clock.advance(Duration::from_secs(25 * 60));
+/// app.on_tick();
+///
+/// assert_eq!(app.get_phase().get_name(), "Rest");
+/// ```
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    instant: Instant,
+    system_time: SystemTime,
+    offsets: Arc<Mutex<(Duration, Duration)>>, // (monotonic, wall) since creation
+}
+
+impl ManualClock {
+    /// Starts at the actual time.
+    pub fn new() -> ManualClock {
+        ManualClock::at(SystemTime::now())
+    }
+
+    /// Starts at `system_time`, e.g. a given time of day on a given weekday.
+    pub fn at(system_time: SystemTime) -> ManualClock {
+        ManualClock {
+            instant: Instant::now(),
+            system_time,
+            offsets: Arc::new(Mutex::new((Duration::ZERO, Duration::ZERO))),
+        }
+    }
+
+    /// Moves both the monotonic and wall clock times forward.
+    pub fn advance(&self, duration: Duration) {
+        let mut offsets = self.offsets.lock().unwrap();
+        offsets.0 += duration;
+        offsets.1 += duration;
+    }
+
+    /// Moves only the wall clock time forward, as while the machine is
+    /// suspended.
+    pub fn suspend(&self, duration: Duration) {
+        self.offsets.lock().unwrap().1 += duration;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> ManualClock {
+        ManualClock::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn instant(&self) -> Instant {
+        self.instant + self.offsets.lock().unwrap().0
+    }
+
+    fn system_time(&self) -> SystemTime {
+        self.system_time + self.offsets.lock().unwrap().1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_only_when_told_to() {
+        let clock = ManualClock::at(SystemTime::UNIX_EPOCH);
+        let shared = clock.clone();
+        let instant = clock.instant();
+
+        assert_eq!(clock.system_time(), SystemTime::UNIX_EPOCH);
+
+        shared.advance(Duration::from_secs(60));
+        assert_eq!(clock.instant() - instant, Duration::from_secs(60));
+        assert_eq!(
+            clock.system_time(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(60)
+        );
+
+        // the monotonic time stands still while suspended
+        shared.suspend(Duration::from_secs(60));
+        assert_eq!(clock.instant() - instant, Duration::from_secs(60));
+        assert_eq!(
+            clock.system_time(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(120)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Events of the timers and what subscribes to them.
+
+use crate::checkpoint::Checkpoint;
+use crate::phase::Phase;
+
+/// Transitions of the timer, taken from [`crate::app::App::take_events`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimerEvent {
+    /// The phase became the current one, running or not.
+    PhaseStarted(Phase),
+    /// The phase started running for the first time, by hand, on its own or
+    /// while catching up on a restored session.
+    PhaseRunning(Phase),
+    /// The phase's time is up, or a phase counting up was ended by hand.
+    PhaseCompleted {
+        /// The phase that ended.
+        phase: Phase,
+        /// The phase coming after it.
+        next: Phase,
+    },
+    /// The phase was skipped.
+    Skipped {
+        /// The phase that was skipped.
+        phase: Phase,
+        /// The phase coming after it.
+        next: Phase,
+    },
+    /// The time the session was scheduled to start at came.
+    ScheduledStart(Phase),
+    /// The running phase was paused.
+    Paused,
+    /// The phase was resumed after a pause.
+    Resumed,
+    /// The timer was stopped and went back to the first phase.
+    Stopped,
+    /// The displayed time changed while running.
+    Tick {
+        /// As [`crate::app::App::get_progress_secs`] reads.
+        progress: u64,
+    },
+    /// Reached within the running phase.
+    Checkpoint(Checkpoint),
+    /// As many focus phases as the daily goal were completed today.
+    GoalReached {
+        /// Focus phases completed today.
+        completed: u64,
+    },
+    /// The working hours are over, the timer was stopped.
+    EndOfDay,
+    /// A micro-break is due, the phase keeps running.
+    MicroBreakStarted {
+        /// How long it lasts, in seconds.
+        secs: u64,
+    },
+    /// The micro-break is over.
+    MicroBreakEnded,
+}
+
+impl TimerEvent {
+    /// The kebab-case name of the event, e.g. "phase-completed".
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimerEvent::PhaseStarted(_) => "phase-started",
+            TimerEvent::PhaseRunning(_) => "phase-running",
+            TimerEvent::PhaseCompleted { .. } => "phase-completed",
+            TimerEvent::Skipped { .. } => "skipped",
+            TimerEvent::ScheduledStart(_) => "scheduled-start",
+            TimerEvent::Paused => "paused",
+            TimerEvent::Resumed => "resumed",
+            TimerEvent::Stopped => "stopped",
+            TimerEvent::Tick { .. } => "tick",
+            TimerEvent::Checkpoint(_) => "checkpoint",
+            TimerEvent::GoalReached { .. } => "goal-reached",
+            TimerEvent::EndOfDay => "end-of-day",
+            TimerEvent::MicroBreakStarted { .. } => "micro-break-started",
+            TimerEvent::MicroBreakEnded => "micro-break-ended",
+        }
+    }
+}
+
+/// Reacts to the events published on an [`EventBus`], along with the name of
+/// the timer they come from.
+pub trait Subscriber {
+    /// Called for each event, along with the name of the timer it's from.
+    fn on_event(&mut self, timer: &str, event: &TimerEvent);
+}
+
+/// Hands every event to each subscriber, in the order they subscribed.
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: Vec<Box<dyn Subscriber>>,
+}
+
+impl EventBus {
+    /// A bus without subscribers.
+    pub fn new() -> EventBus {
+        EventBus {
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Adds a subscriber, called after the previous ones.
+    pub fn subscribe(&mut self, subscriber: Box<dyn Subscriber>) {
+        self.subscribers.push(subscriber);
+    }
+
+    /// Hands `events`, from the timer named `timer`, to every subscriber.
+    pub fn publish(&mut self, timer: &str, events: Vec<TimerEvent>) {
+        for event in events {
+            for subscriber in self.subscribers.iter_mut() {
+                subscriber.on_event(timer, &event);
+            }
+        }
+    }
+}
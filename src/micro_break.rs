@@ -0,0 +1,76 @@
+//! Micro-breaks nested in focus phases.
+
+use crate::phase::parse_duration;
+use crate::utils::MyResult;
+
+/// A short rest nested in focus phases, e.g. looking away for 20 seconds
+/// every 20 minutes, that leaves the phase running.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MicroBreak {
+    every: u64,    // seconds of focus
+    duration: u64, // in seconds
+}
+
+impl MicroBreak {
+    /// One lasting `duration` seconds every `every` seconds of focus.
+    pub fn new(every: u64, duration: u64) -> MicroBreak {
+        MicroBreak { every, duration }
+    }
+
+    /// Parses how often and how long, e.g. "20m:20s".
+    pub fn parse(spec: &str) -> MyResult<MicroBreak> {
+        spec.trim()
+            .split_once(':')
+            .and_then(|(every, duration)| {
+                Some(MicroBreak::new(
+                    parse_duration(every.trim())?,
+                    parse_duration(duration.trim())?,
+                ))
+            })
+            .filter(|micro_break| micro_break.every > 0)
+            .ok_or_else(|| format!("invalid micro-break `{}`", spec.trim()).into())
+    }
+
+    /// In seconds.
+    pub fn get_duration(&self) -> u64 {
+        self.duration
+    }
+
+    /// Whether one is due between `from` and `to` seconds into a phase, none
+    /// coinciding with its end, nor any when `every` is 0.
+    pub fn is_due(&self, from: u64, to: u64, phase_duration: Option<u64>) -> bool {
+        self.every > 0
+            && to / self.every > from / self.every
+            && phase_duration.is_none_or(|phase_duration| to < phase_duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_micro_breaks() {
+        assert_eq!(
+            MicroBreak::parse(" 20m : 20s ").unwrap(),
+            MicroBreak::new(20 * 60, 20)
+        );
+
+        for spec in ["", "20m", "0s:20s", "20q:20s", "20m:x"] {
+            assert!(MicroBreak::parse(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn is_due_every_so_often() {
+        let micro_break = MicroBreak::new(20 * 60, 20);
+
+        assert!(!micro_break.is_due(0, 19 * 60, Some(60 * 60)));
+        assert!(micro_break.is_due(19 * 60, 20 * 60, Some(60 * 60)));
+        assert!(micro_break.is_due(39 * 60, 41 * 60, None));
+        // none coinciding with the end of the phase
+        assert!(!micro_break.is_due(59 * 60, 60 * 60, Some(60 * 60)));
+        // nor any at all without an interval
+        assert!(!MicroBreak::new(0, 20).is_due(0, 60 * 60, None));
+    }
+}
@@ -3,12 +3,25 @@ use rodio::{Decoder, OutputStream, Sink};
 use std::io::BufReader;
 use std::{fs, thread};
 
+use crate::events::{Subscriber, TimerEvent};
+use crate::phase::{Phase, PhaseKind};
+
+// a sound that can't be played, e.g. a mistyped checkpoint sound, is skipped
 fn play_sound(path: &str) {
-    let (_stream, handle) = OutputStream::try_default().unwrap();
-    let sink = Sink::try_new(&handle).unwrap();
+    let Ok((_stream, handle)) = OutputStream::try_default() else {
+        return;
+    };
+    let Ok(sink) = Sink::try_new(&handle) else {
+        return;
+    };
+    let Ok(file) = fs::File::open(path) else {
+        return;
+    };
+    let Ok(source) = Decoder::new(BufReader::new(file)) else {
+        return;
+    };
 
-    let file = fs::File::open(path).unwrap();
-    sink.append(Decoder::new(BufReader::new(file)).unwrap());
+    sink.append(source);
     sink.sleep_until_end();
 }
 
@@ -27,22 +40,68 @@ impl Notification {
         self.notifier.body(body).summary(summary);
     }
 
-    fn notify(&self, sound_path: &'static str) {
+    fn notify(&self, sound_path: &str) {
         let notify_clone = self.notifier.clone();
+        let sound_path = sound_path.to_string();
 
         thread::spawn(move || {
-            notify_clone.show().unwrap();
-            play_sound(sound_path);
+            let _ = notify_clone.show();
+            play_sound(&sound_path);
         });
     }
 
-    pub fn notify_work(&mut self) {
-        self.update_body_and_summary("Work time", "It's time to work");
+    // named after the timer that fired, as several can run side by side
+    pub fn notify_phase(&mut self, timer: &str, phase: &Phase) {
+        let body = format!("{}: {}", timer, phase.get_name());
+
+        match phase.get_kind() {
+            PhaseKind::Focus => {
+                self.update_body_and_summary(&body, "It's time to work");
+                self.notify("assets/OGG_Polite.ogg");
+            }
+            PhaseKind::Rest if phase.is_long_break() => {
+                self.update_body_and_summary(&body, "Take a long break, you've earned it");
+                self.notify("assets/OGG_Calm.ogg");
+            }
+            PhaseKind::Rest => {
+                self.update_body_and_summary(&body, "Take a break");
+                self.notify("assets/OGG_Calm.ogg");
+            }
+        }
+    }
+
+    pub fn notify_goal(&mut self, completed: u64) {
+        self.update_body_and_summary(
+            "Daily goal reached",
+            &format!("{} pomodoros done today", completed),
+        );
         self.notify("assets/OGG_Polite.ogg");
     }
+}
 
-    pub fn notify_break(&mut self) {
-        self.update_body_and_summary("Break time", "Take a break");
-        self.notify("assets/OGG_Calm.ogg");
+impl Subscriber for Notification {
+    fn on_event(&mut self, timer: &str, event: &TimerEvent) {
+        match event {
+            TimerEvent::PhaseCompleted { next, .. }
+            | TimerEvent::Skipped { next, .. }
+            | TimerEvent::ScheduledStart(next) => self.notify_phase(timer, next),
+            TimerEvent::GoalReached { completed } => self.notify_goal(*completed),
+            TimerEvent::MicroBreakStarted { secs } => {
+                self.update_body_and_summary(
+                    timer,
+                    &format!("Look into the distance for {} seconds", secs),
+                );
+                self.notify("assets/OGG_Calm.ogg");
+            }
+            TimerEvent::EndOfDay => {
+                self.update_body_and_summary(timer, "End of the working day");
+                self.notify("assets/OGG_Calm.ogg");
+            }
+            TimerEvent::Checkpoint(checkpoint) => {
+                self.update_body_and_summary(timer, checkpoint.get_message());
+                self.notify(checkpoint.get_sound().unwrap_or("assets/OGG_Calm.ogg"));
+            }
+            _ => (),
+        }
     }
 }
@@ -0,0 +1,496 @@
+//! Phases and the sequences the timer loops over.
+
+use chrono::{Local, NaiveTime};
+
+use crate::utils::{secs_until, to_secs, MyResult};
+
+/// Whether a phase is for working or resting.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PhaseKind {
+    /// Working, counted towards the daily goal.
+    Focus,
+    /// A break.
+    Rest,
+}
+
+/// How a phase is shown, mapped to a terminal color by the TUI.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PhaseColor {
+    /// The default for focus phases.
+    Yellow,
+    /// The default for rest phases.
+    Green,
+    /// Cyan.
+    Cyan,
+    /// Blue.
+    Blue,
+    /// Magenta.
+    Magenta,
+    /// Red.
+    Red,
+}
+
+/// A stretch of focus or rest, lasting a fixed duration, counting up until
+/// ended by hand or ending at a time of day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Phase {
+    name: String,
+    duration: u64, // in seconds
+    kind: PhaseKind,
+    color: PhaseColor,
+    count_up: bool,           // open ended, counts up from zero until ended by hand
+    until: Option<NaiveTime>, // ends at a time of day rather than after `duration`
+    long_break: bool,         // the rest earned by a run of focus phases
+}
+
+impl Phase {
+    /// A phase lasting `duration` seconds.
+    pub fn new(name: &str, duration: u64, kind: PhaseKind, color: PhaseColor) -> Phase {
+        Phase {
+            name: name.to_string(),
+            duration,
+            kind,
+            color,
+            count_up: false,
+            until: None,
+            long_break: false,
+        }
+    }
+
+    /// A phase ending at the time of day `until`.
+    pub fn until(name: &str, until: NaiveTime, kind: PhaseKind, color: PhaseColor) -> Phase {
+        Phase {
+            until: Some(until),
+            ..Phase::new(name, 0, kind, color)
+        }
+    }
+
+    /// A phase counting up from zero until ended by hand.
+    pub fn count_up(name: &str, kind: PhaseKind, color: PhaseColor) -> Phase {
+        Phase {
+            count_up: true,
+            ..Phase::new(name, 0, kind, color)
+        }
+    }
+
+    /// Shown in the UI and the notifications.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// In seconds, for phases ending at a time of day the duration if started
+    /// now.
+    pub fn get_duration(&self) -> u64 {
+        self.get_duration_at(Local::now().time())
+    }
+
+    /// In seconds, for phases ending at a time of day the duration if started
+    /// at `now`.
+    pub fn get_duration_at(&self, now: NaiveTime) -> u64 {
+        match self.until {
+            Some(until) => secs_until(until, now),
+            None => self.duration,
+        }
+    }
+
+    /// Makes the phase last `secs` seconds, rather than end at a time of day.
+    pub fn set_duration(&mut self, secs: u64) {
+        self.duration = secs;
+        self.until = None;
+    }
+
+    /// The time of day the phase ends at, if any.
+    pub fn get_until(&self) -> Option<NaiveTime> {
+        self.until
+    }
+
+    /// Makes the phase end at the time of day `until`.
+    pub fn set_until(&mut self, until: NaiveTime) {
+        self.until = Some(until);
+    }
+
+    /// Focus or rest.
+    pub fn get_kind(&self) -> PhaseKind {
+        self.kind
+    }
+
+    /// How the phase is shown.
+    pub fn get_color(&self) -> PhaseColor {
+        self.color
+    }
+
+    /// Whether the phase is for working.
+    pub fn is_focus(&self) -> bool {
+        matches!(self.kind, PhaseKind::Focus)
+    }
+
+    /// Whether the phase is the long break earned by a run of focus phases.
+    pub fn is_long_break(&self) -> bool {
+        self.long_break
+    }
+
+    /// Makes the phase a long break, or not.
+    pub fn set_long_break(&mut self, long_break: bool) {
+        self.long_break = long_break;
+    }
+
+    /// Whether the phase counts up until ended by hand.
+    pub fn is_count_up(&self) -> bool {
+        self.count_up
+    }
+}
+
+/// How long the break after a phase counting up lasts.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BreakRule {
+    /// 1/N of the time worked.
+    Fraction(u64),
+    /// (worked up to, break) in seconds, sorted, the last step applies beyond.
+    Steps(Vec<(u64, u64)>),
+}
+
+impl BreakRule {
+    /// Parses a fraction, "1/5", or steps such as "25m:5m, 50m:8m, 90m:10m".
+    pub fn parse(spec: &str) -> MyResult<BreakRule> {
+        if let Some(n) = spec.trim().strip_prefix("1/") {
+            let n = n
+                .parse::<u64>()
+                .map_err(|_| format!("invalid fraction `{}`", spec))?;
+
+            return Ok(BreakRule::Fraction(n));
+        }
+
+        let mut steps = Vec::new();
+
+        for step in spec.split(',') {
+            let (up_to, break_secs) = step
+                .trim()
+                .split_once(':')
+                .and_then(|(up_to, break_secs)| {
+                    Some((parse_duration(up_to)?, parse_duration(break_secs)?))
+                })
+                .ok_or_else(|| format!("invalid break step `{}`", step.trim()))?;
+
+            steps.push((up_to, break_secs));
+        }
+
+        steps.sort();
+
+        Ok(BreakRule::Steps(steps))
+    }
+
+    /// The break earned by `worked` seconds, in seconds.
+    pub fn break_for(&self, worked: u64) -> u64 {
+        match self {
+            BreakRule::Fraction(n) => worked / (*n).max(1),
+            BreakRule::Steps(steps) => steps
+                .iter()
+                .find(|(up_to, _)| worked <= *up_to)
+                .or(steps.last())
+                .map_or(0, |(_, break_secs)| *break_secs),
+        }
+    }
+}
+
+/// Ordered list of phases the timer loops over.
+///
+/// Phases are stored once and referenced by index from the order, so editing
+/// a phase updates every occurrence of it in the sequence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sequence {
+    phases: Vec<Phase>,
+    order: Vec<usize>,
+}
+
+impl Sequence {
+    /// Work and break alternating, with a long break after every `interval`
+    /// work phases.
+    pub fn classic(
+        work_phase: Phase,
+        break_phase: Phase,
+        mut long_break_phase: Phase,
+        interval: usize,
+    ) -> Sequence {
+        let mut order = Vec::new();
+
+        long_break_phase.set_long_break(true);
+
+        for _ in 1..interval.max(1) {
+            order.extend([0, 1]);
+        }
+        order.extend([0, 2]);
+
+        Sequence {
+            phases: vec![work_phase, break_phase, long_break_phase],
+            order,
+        }
+    }
+
+    /// Parses comma separated phases, e.g. "focus 90m, rest 20m" or
+    /// "focus 25m, rest 5m x3, focus 25m, long 15m".
+    ///
+    /// `long` is a rest phase that is a long break, named "Long break" unless
+    /// named otherwise.
+    /// `xN` (or `×N`) repeats the items since the previous repetition N times,
+    /// `open` instead of a duration makes a phase count up until ended by hand
+    /// and `@11:55` makes it end at that time of day.
+    pub fn parse(spec: &str) -> MyResult<Sequence> {
+        let mut sequence = Sequence {
+            phases: Vec::new(),
+            order: Vec::new(),
+        };
+        let mut group_start = 0;
+
+        for item in spec.split(',') {
+            let mut words: Vec<&str> = item.split_whitespace().collect();
+            let mut repeat = 1;
+
+            if let Some(count) = words
+                .last()
+                .and_then(|last| last.strip_prefix('x').or_else(|| last.strip_prefix('×')))
+                .and_then(|count| count.parse::<usize>().ok())
+            {
+                repeat = count;
+                words.pop();
+            }
+
+            let (kind, color, default_name) = match words.first() {
+                Some(&"focus") => (PhaseKind::Focus, PhaseColor::Yellow, "Focus"),
+                Some(&"rest") => (PhaseKind::Rest, PhaseColor::Green, "Rest"),
+                Some(&"long") => (PhaseKind::Rest, PhaseColor::Cyan, "Long break"),
+                _ => {
+                    return Err(
+                        format!("expected `focus`, `rest` or `long` in `{}`", item.trim()).into(),
+                    )
+                }
+            };
+            let name = if words.len() > 2 {
+                words[2..].join(" ")
+            } else {
+                default_name.to_string()
+            };
+            let mut phase = match words.get(1) {
+                Some(&"open") => Phase::count_up(&name, kind, color),
+                Some(until) if until.starts_with('@') => {
+                    let until = NaiveTime::parse_from_str(&until[1..], "%H:%M")
+                        .map_err(|_| format!("invalid time of day in `{}`", item.trim()))?;
+
+                    Phase::until(&name, until, kind, color)
+                }
+                duration => {
+                    let duration = duration
+                        .and_then(|duration| parse_duration(duration))
+                        .ok_or_else(|| format!("invalid duration in `{}`", item.trim()))?;
+
+                    Phase::new(&name, duration, kind, color)
+                }
+            };
+
+            phase.set_long_break(words.first() == Some(&"long"));
+
+            sequence.push(phase);
+
+            if repeat != 1 {
+                let group = sequence.order[group_start..].to_vec();
+
+                for _ in 1..repeat {
+                    sequence.order.extend(&group);
+                }
+                group_start = sequence.order.len();
+            }
+        }
+
+        if sequence.order.is_empty() {
+            return Err("empty phase sequence".into());
+        }
+
+        Ok(sequence)
+    }
+
+    fn push(&mut self, phase: Phase) {
+        let index = self
+            .phases
+            .iter()
+            .position(|p| {
+                p.name == phase.name
+                    && p.duration == phase.duration
+                    && p.kind == phase.kind
+                    && p.count_up == phase.count_up
+                    && p.until == phase.until
+                    && p.long_break == phase.long_break
+            })
+            .unwrap_or_else(|| {
+                self.phases.push(phase);
+                self.phases.len() - 1
+            });
+
+        self.order.push(index);
+    }
+
+    /// Number of positions in the sequence, repetitions included.
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Never, once parsed or built.
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// The phase at `position` in the sequence.
+    pub fn get(&self, position: usize) -> &Phase {
+        &self.phases[self.order[position]]
+    }
+
+    /// The distinct phases, each stored once however often it occurs.
+    pub fn get_phases(&self) -> &[Phase] {
+        &self.phases
+    }
+
+    /// Makes the phase at `index` in [`Sequence::get_phases`] last `secs`
+    /// seconds.
+    pub fn set_duration(&mut self, index: usize, secs: u64) {
+        if let Some(phase) = self.phases.get_mut(index) {
+            phase.set_duration(secs);
+        }
+    }
+
+    /// Makes the phase at `index` in [`Sequence::get_phases`] end at the time
+    /// of day `until`.
+    pub fn set_until(&mut self, index: usize, until: NaiveTime) {
+        if let Some(phase) = self.phases.get_mut(index) {
+            phase.set_until(until);
+        }
+    }
+
+    /// First position in the sequence of the phase at `index` in
+    /// [`Sequence::get_phases`].
+    pub fn position_of(&self, index: usize) -> Option<usize> {
+        self.order.iter().position(|&i| i == index)
+    }
+}
+
+/// Parses a duration in seconds, e.g. "90m", "1h30m" or "45s".
+pub fn parse_duration(s: &str) -> Option<u64> {
+    let (mut hours, mut minutes, mut secs) = (0, 0, 0);
+    let mut number = String::new();
+
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let value = number.parse::<u64>().ok()?;
+        number.clear();
+
+        match c {
+            'h' => hours = value,
+            'm' => minutes = value,
+            's' => secs = value,
+            _ => return None,
+        }
+    }
+
+    if !number.is_empty() {
+        return None;
+    }
+
+    Some(to_secs(hours, minutes, secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sequences() {
+        let sequence =
+            Sequence::parse("focus 25m, rest 5m x3, focus 25m, rest 15m Long break").unwrap();
+
+        assert_eq!(sequence.len(), 8);
+        assert_eq!(sequence.get_phases().len(), 3);
+        assert_eq!(sequence.get(6), sequence.get(0));
+        assert_eq!(sequence.get(7).get_name(), "Long break");
+        assert_eq!(sequence.get(7).get_duration(), 15 * 60);
+        assert_eq!(sequence.get(7).get_kind(), PhaseKind::Rest);
+        assert!(!sequence.get(7).is_long_break());
+
+        let sequence = Sequence::parse("focus 25m, long 15m").unwrap();
+
+        assert_eq!(sequence.get(1).get_name(), "Long break");
+        assert_eq!(sequence.get(1).get_kind(), PhaseKind::Rest);
+        assert!(sequence.get(1).is_long_break());
+    }
+
+    #[test]
+    fn rejects_invalid_sequences() {
+        for spec in [
+            "",
+            "nap 5m",
+            "focus",
+            "focus 5q",
+            "rest @25:00",
+            "focus 25m,",
+        ] {
+            assert!(Sequence::parse(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("1h30m"), Some(90 * 60));
+        assert_eq!(parse_duration("45s"), Some(45));
+        assert_eq!(parse_duration("90"), None);
+    }
+
+    #[test]
+    fn parses_phases_counting_up() {
+        let sequence = Sequence::parse("focus open Flow, rest 5m").unwrap();
+
+        assert!(sequence.get(0).is_count_up());
+        assert_eq!(sequence.get(0).get_name(), "Flow");
+        assert!(!sequence.get(1).is_count_up());
+    }
+
+    #[test]
+    fn parses_break_rules() {
+        assert_eq!(BreakRule::parse("1/5").unwrap(), BreakRule::Fraction(5));
+        assert_eq!(BreakRule::parse("1/5").unwrap().break_for(50 * 60), 10 * 60);
+
+        let rule = BreakRule::parse("50m:8m, 25m:5m").unwrap();
+
+        assert_eq!(
+            rule,
+            BreakRule::Steps(vec![(25 * 60, 5 * 60), (50 * 60, 8 * 60)])
+        );
+        assert_eq!(rule.break_for(20 * 60), 5 * 60);
+        assert_eq!(rule.break_for(40 * 60), 8 * 60);
+        assert_eq!(rule.break_for(90 * 60), 8 * 60);
+    }
+
+    #[test]
+    fn rejects_invalid_break_rules() {
+        for spec in ["1/x", "25m", "25m:5q", ""] {
+            assert!(BreakRule::parse(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn parses_phases_ending_at_a_time_of_day() {
+        let sequence = Sequence::parse("focus 25m, rest @11:55").unwrap();
+
+        assert_eq!(sequence.get(0).get_until(), None);
+        assert_eq!(
+            sequence.get(1).get_until(),
+            NaiveTime::from_hms_opt(11, 55, 0)
+        );
+    }
+
+    #[test]
+    fn tells_phases_apart_by_how_they_end() {
+        let sequence = Sequence::parse("focus 25m, focus open, focus @17:00").unwrap();
+
+        assert_eq!(sequence.get_phases().len(), 3);
+    }
+}
@@ -0,0 +1,278 @@
+//! Summaries of the session's history.
+
+use chrono::NaiveDate;
+
+use crate::{app::App, phase::PhaseKind};
+
+/// Estimated against actual pomodoros of the tasks completed on a day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EstimationRow {
+    /// The day the tasks were completed.
+    pub date: NaiveDate,
+    /// Number of tasks completed.
+    pub tasks: u64,
+    /// Pomodoros estimated for them.
+    pub estimated: u64,
+    /// Pomodoros actually spent on them.
+    pub actual: u64,
+    /// In percent, 100 when every estimate was spot on.
+    pub accuracy: u16,
+}
+
+/// One row per day tasks with an estimate were completed, oldest first.
+pub fn estimation_report(app: &App) -> Vec<EstimationRow> {
+    let mut rows: Vec<EstimationRow> = Vec::new();
+    let mut accuracies: Vec<f32> = Vec::new(); // summed per row
+
+    for (index, task) in app.get_tasks().iter().enumerate() {
+        let (Some(done_at), Some(estimated)) = (task.get_done_at(), task.get_estimate()) else {
+            continue;
+        };
+        let date = done_at.date_naive();
+        let actual = app.get_task_pomodoros(index);
+        // the smaller of the two over the larger, so overruns and
+        // underruns weigh the same
+        let accuracy = if estimated.max(actual) == 0 {
+            1.0
+        } else {
+            estimated.min(actual) as f32 / estimated.max(actual) as f32
+        };
+
+        let position = match rows.iter().position(|row| row.date == date) {
+            Some(position) => position,
+            None => {
+                rows.push(EstimationRow {
+                    date,
+                    tasks: 0,
+                    estimated: 0,
+                    actual: 0,
+                    accuracy: 0,
+                });
+                accuracies.push(0.0);
+                rows.len() - 1
+            }
+        };
+        let row = &mut rows[position];
+
+        row.tasks += 1;
+        row.estimated += estimated;
+        row.actual += actual;
+        accuracies[position] += accuracy;
+        row.accuracy = (accuracies[position] / row.tasks as f32 * 100.0) as u16;
+    }
+
+    rows.sort_by_key(|row| row.date);
+
+    rows
+}
+
+/// Time actually spent on focus phases on a day, pauses excluded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FocusRow {
+    /// The day the phases ended.
+    pub date: NaiveDate,
+    /// Number of focus phases.
+    pub phases: u64,
+    /// In seconds.
+    pub focused: u64,
+    /// In seconds.
+    pub paused: u64,
+}
+
+/// One row per day with focus phases, oldest first.
+pub fn focus_report(app: &App) -> Vec<FocusRow> {
+    let mut rows: Vec<FocusRow> = Vec::new();
+
+    for record in app.get_history() {
+        if !matches!(record.kind, PhaseKind::Focus) {
+            continue;
+        }
+
+        let date = record.ended_at.date_naive();
+        let position = match rows.iter().position(|row| row.date == date) {
+            Some(position) => position,
+            None => {
+                rows.push(FocusRow {
+                    date,
+                    phases: 0,
+                    focused: 0,
+                    paused: 0,
+                });
+                rows.len() - 1
+            }
+        };
+        let row = &mut rows[position];
+
+        row.phases += 1;
+        row.focused += record.active;
+        row.paused += record
+            .pauses
+            .iter()
+            .map(|pause| pause.get_duration(record.ended_at))
+            .sum::<u64>();
+    }
+
+    rows.sort_by_key(|row| row.date);
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::app::{AppStatus, Pause, PhaseOutcome, PhaseRecord};
+    use crate::clock::ManualClock;
+    use crate::phase::Sequence;
+    use crate::state::State;
+    use crate::task::{Task, TaskList};
+    use chrono::{DateTime, Local, TimeZone};
+    use std::sync::Arc;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2024, 1, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    fn focus(task: Option<usize>, outcome: PhaseOutcome, ended_at: DateTime<Local>) -> PhaseRecord {
+        PhaseRecord {
+            kind: PhaseKind::Focus,
+            outcome,
+            overrun: 0,
+            ended_at,
+            interruptions: Vec::new(),
+            task,
+            pauses: Vec::new(),
+            active: 25 * 60,
+        }
+    }
+
+    // as reloaded from a saved session
+    fn app_with(tasks: TaskList, history: Vec<PhaseRecord>) -> App {
+        let saved_at = at(3, 9, 0);
+        let mut app = App::builder(Sequence::parse("focus 25m, rest 5m").unwrap())
+            .clock(Arc::new(ManualClock::at(saved_at.into())))
+            .build();
+
+        app.restore(&State {
+            position: 0,
+            status: AppStatus::Paused,
+            progress: 25 * 60,
+            phase_duration: 25 * 60,
+            extensions: 0,
+            countdown: 0,
+            saved_at,
+            scheduled_at: None,
+            tasks,
+            history,
+        });
+
+        app
+    }
+
+    #[test]
+    fn weighs_estimates_against_pomodoros_per_day() {
+        let mut tasks = TaskList::new();
+
+        tasks.push(Task::new("Spot on", Some(2), Some(at(1, 12, 0))));
+        tasks.push(Task::new("Underestimated", Some(4), Some(at(1, 15, 0))));
+        tasks.push(Task::new("Overestimated", Some(1), Some(at(2, 12, 0))));
+        tasks.push(Task::new("Not estimated", None, Some(at(1, 12, 0))));
+        tasks.push(Task::new("Not done", Some(2), None));
+
+        let app = app_with(
+            tasks,
+            vec![
+                focus(Some(0), PhaseOutcome::Completed, at(1, 10, 0)),
+                focus(Some(0), PhaseOutcome::Completed, at(1, 11, 0)),
+                focus(Some(1), PhaseOutcome::Completed, at(1, 14, 0)),
+                // skipped pomodoros don't count
+                focus(Some(1), PhaseOutcome::Skipped, at(1, 14, 30)),
+                focus(Some(2), PhaseOutcome::Completed, at(2, 10, 0)),
+                focus(Some(2), PhaseOutcome::Completed, at(2, 11, 0)),
+                focus(Some(2), PhaseOutcome::Completed, at(2, 11, 30)),
+                focus(Some(3), PhaseOutcome::Completed, at(1, 11, 30)),
+                focus(Some(4), PhaseOutcome::Completed, at(2, 13, 0)),
+            ],
+        );
+
+        assert_eq!(
+            estimation_report(&app),
+            [
+                // 2 of 2 and 1 of 4, averaged
+                EstimationRow {
+                    date: date(1),
+                    tasks: 2,
+                    estimated: 6,
+                    actual: 3,
+                    accuracy: 62,
+                },
+                // 1 of 3
+                EstimationRow {
+                    date: date(2),
+                    tasks: 1,
+                    estimated: 1,
+                    actual: 3,
+                    accuracy: 33,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn sets_pauses_apart_from_the_time_focused() {
+        let mut paused = focus(None, PhaseOutcome::Completed, at(1, 10, 0));
+        paused.pauses = vec![Pause {
+            started_at: at(1, 9, 40),
+            ended_at: Some(at(1, 9, 45)),
+            reason: None,
+        }];
+
+        // a pause still open counts up to the end of the phase
+        let mut skipped = focus(None, PhaseOutcome::Skipped, at(1, 11, 0));
+        skipped.active = 10 * 60;
+        skipped.pauses = vec![Pause {
+            started_at: at(1, 10, 50),
+            ended_at: None,
+            reason: None,
+        }];
+
+        let mut rest = focus(None, PhaseOutcome::Completed, at(1, 11, 5));
+        rest.kind = PhaseKind::Rest;
+        rest.active = 5 * 60;
+        rest.pauses = paused.pauses.clone();
+
+        let app = app_with(
+            TaskList::new(),
+            vec![
+                focus(None, PhaseOutcome::Completed, at(2, 10, 0)),
+                paused,
+                skipped,
+                rest,
+            ],
+        );
+
+        assert_eq!(
+            focus_report(&app),
+            [
+                FocusRow {
+                    date: date(1),
+                    phases: 2,
+                    focused: 35 * 60,
+                    paused: 15 * 60,
+                },
+                FocusRow {
+                    date: date(2),
+                    phases: 1,
+                    focused: 25 * 60,
+                    paused: 0,
+                },
+            ]
+        );
+    }
+}
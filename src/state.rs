@@ -0,0 +1,408 @@
+//! Saving and loading a session.
+
+use chrono::{DateTime, Local, NaiveTime};
+use std::fs;
+
+use crate::app::{
+    AppStatus, Interruption, InterruptionKind, Pause, PauseReason, PhaseOutcome, PhaseRecord,
+};
+use crate::phase::PhaseKind;
+use crate::task::{Task, TaskList};
+use crate::utils::MyResult;
+
+/// What's needed to pick a session back up after a restart, see
+/// [`crate::app::App::get_state`] and [`crate::app::App::restore`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct State {
+    /// Of the current phase in the sequence, starting at 0.
+    pub position: usize,
+    /// Running, paused, starting, overtime or scheduled.
+    pub status: AppStatus,
+    /// As [`crate::app::App::get_progress_secs`] read when saved.
+    pub progress: u64,
+    /// In seconds, including extensions.
+    pub phase_duration: u64,
+    /// Of the current phase.
+    pub extensions: u64,
+    /// Seconds left of the auto-start countdown, or until the scheduled start.
+    pub countdown: u64,
+    /// When the state was taken.
+    pub saved_at: DateTime<Local>,
+    /// The time of day the session is scheduled to start at.
+    pub scheduled_at: Option<NaiveTime>,
+    /// The tasks of the session.
+    pub tasks: TaskList,
+    /// The phases ended so far.
+    pub history: Vec<PhaseRecord>,
+}
+
+fn format_time(time: Option<DateTime<Local>>) -> String {
+    time.map_or("-".to_string(), |time| time.to_rfc3339())
+}
+
+fn parse_time(value: &str) -> Option<Option<DateTime<Local>>> {
+    match value {
+        "-" => Some(None),
+        value => Some(Some(
+            DateTime::parse_from_rfc3339(value)
+                .ok()?
+                .with_timezone(&Local),
+        )),
+    }
+}
+
+fn parse_pause_reason(value: &str) -> Option<Option<PauseReason>> {
+    match value {
+        "-" => Some(None),
+        value => PauseReason::ALL
+            .iter()
+            .chain([&PauseReason::Away])
+            .find(|reason| reason.label().to_lowercase() == value)
+            .map(|reason| Some(*reason)),
+    }
+}
+
+// One `key value` per line, tasks as `task <estimate|-> <done at|-> <name>` and
+// phase records as `record <kind> <outcome> <overrun> <active> <ended at> <task|->`,
+// each followed by its `interruption <kind> <at>` and `pause <start> <end|-> <reason|->`
+impl State {
+    /// Writes the state to `path`, replacing the previous one at once.
+    pub fn save(&self, path: &str) -> MyResult<()> {
+        let status = match self.status {
+            AppStatus::Running => "running",
+            AppStatus::Starting => "starting",
+            AppStatus::Overtime => "overtime",
+            AppStatus::Scheduled => "scheduled",
+            _ => "paused",
+        };
+        let mut lines = vec![
+            format!("position {}", self.position),
+            format!("status {}", status),
+            format!("progress {}", self.progress),
+            format!("phase_duration {}", self.phase_duration),
+            format!("extensions {}", self.extensions),
+            format!("countdown {}", self.countdown),
+            format!("saved_at {}", self.saved_at.to_rfc3339()),
+        ];
+
+        if let Some(scheduled_at) = self.scheduled_at {
+            lines.push(format!("scheduled_at {}", scheduled_at.format("%H:%M")));
+        }
+
+        if let Some(active) = self.tasks.get_active() {
+            lines.push(format!("active {}", active));
+        }
+
+        for task in self.tasks.get_tasks() {
+            lines.push(format!(
+                "task {} {} {}",
+                task.get_estimate()
+                    .map_or("-".to_string(), |estimate| estimate.to_string()),
+                task.get_done_at()
+                    .map_or("-".to_string(), |done_at| done_at.to_rfc3339()),
+                task.get_name()
+            ));
+        }
+
+        for record in &self.history {
+            lines.push(format!(
+                "record {} {} {} {} {} {}",
+                match record.kind {
+                    PhaseKind::Focus => "focus",
+                    PhaseKind::Rest => "rest",
+                },
+                match record.outcome {
+                    PhaseOutcome::Completed => "completed",
+                    PhaseOutcome::Skipped => "skipped",
+                },
+                record.overrun,
+                record.active,
+                record.ended_at.to_rfc3339(),
+                record.task.map_or("-".to_string(), |task| task.to_string())
+            ));
+
+            for interruption in &record.interruptions {
+                lines.push(format!(
+                    "interruption {} {}",
+                    match interruption.kind {
+                        InterruptionKind::Internal => "internal",
+                        InterruptionKind::External => "external",
+                        InterruptionKind::Away => "away",
+                    },
+                    interruption.at.to_rfc3339()
+                ));
+            }
+
+            for pause in &record.pauses {
+                lines.push(format!(
+                    "pause {} {} {}",
+                    pause.started_at.to_rfc3339(),
+                    format_time(pause.ended_at),
+                    pause
+                        .reason
+                        .map_or("-".to_string(), |reason| reason.label().to_lowercase())
+                ));
+            }
+        }
+
+        // written aside first so a crash mid-write doesn't lose the previous state
+        let tmp_path = format!("{}.tmp", path);
+        fs::write(&tmp_path, lines.join("\n") + "\n")?;
+        fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    }
+
+    /// Reads a state written by [`State::save`].
+    pub fn load(path: &str) -> MyResult<State> {
+        let content = fs::read_to_string(path)?;
+        let mut state = State {
+            position: 0,
+            status: AppStatus::Paused,
+            progress: 0,
+            phase_duration: 0,
+            extensions: 0,
+            countdown: 0,
+            saved_at: Local::now(),
+            scheduled_at: None,
+            tasks: TaskList::new(),
+            history: Vec::new(),
+        };
+        let mut active = None;
+
+        for line in content.lines() {
+            let (key, value) = line
+                .split_once(' ')
+                .ok_or_else(|| format!("invalid state line `{}`", line))?;
+            let invalid = || format!("invalid {} `{}`", key, value);
+
+            match key {
+                "position" => state.position = value.parse().map_err(|_| invalid())?,
+                "status" => {
+                    state.status = match value {
+                        "running" => AppStatus::Running,
+                        "starting" => AppStatus::Starting,
+                        "overtime" => AppStatus::Overtime,
+                        "paused" => AppStatus::Paused,
+                        "scheduled" => AppStatus::Scheduled,
+                        _ => return Err(invalid().into()),
+                    }
+                }
+                "progress" => state.progress = value.parse().map_err(|_| invalid())?,
+                "phase_duration" => state.phase_duration = value.parse().map_err(|_| invalid())?,
+                "extensions" => state.extensions = value.parse().map_err(|_| invalid())?,
+                "countdown" => state.countdown = value.parse().map_err(|_| invalid())?,
+                "scheduled_at" => {
+                    state.scheduled_at =
+                        Some(NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| invalid())?)
+                }
+                "saved_at" => {
+                    state.saved_at = DateTime::parse_from_rfc3339(value)
+                        .map_err(|_| invalid())?
+                        .with_timezone(&Local)
+                }
+                "active" => active = Some(value.parse().map_err(|_| invalid())?),
+                "task" => {
+                    let mut fields = value.splitn(3, ' ');
+                    let (estimate, done_at, name) =
+                        match (fields.next(), fields.next(), fields.next()) {
+                            (Some(estimate), Some(done_at), Some(name)) => {
+                                (estimate, done_at, name)
+                            }
+                            _ => return Err(invalid().into()),
+                        };
+                    let estimate = match estimate {
+                        "-" => None,
+                        estimate => Some(estimate.parse().map_err(|_| invalid())?),
+                    };
+                    let done_at = match done_at {
+                        "-" => None,
+                        done_at => Some(
+                            DateTime::parse_from_rfc3339(done_at)
+                                .map_err(|_| invalid())?
+                                .with_timezone(&Local),
+                        ),
+                    };
+
+                    state.tasks.push(Task::new(name, estimate, done_at));
+                }
+                "record" => {
+                    let fields: Vec<&str> = value.split(' ').collect();
+                    let record = match fields[..] {
+                        [kind, outcome, overrun, active, ended_at, task] => PhaseRecord {
+                            kind: match kind {
+                                "focus" => PhaseKind::Focus,
+                                "rest" => PhaseKind::Rest,
+                                _ => return Err(invalid().into()),
+                            },
+                            outcome: match outcome {
+                                "completed" => PhaseOutcome::Completed,
+                                "skipped" => PhaseOutcome::Skipped,
+                                _ => return Err(invalid().into()),
+                            },
+                            overrun: overrun.parse().map_err(|_| invalid())?,
+                            active: active.parse().map_err(|_| invalid())?,
+                            ended_at: parse_time(ended_at).flatten().ok_or_else(invalid)?,
+                            task: match task {
+                                "-" => None,
+                                task => Some(task.parse().map_err(|_| invalid())?),
+                            },
+                            interruptions: Vec::new(),
+                            pauses: Vec::new(),
+                        },
+                        _ => return Err(invalid().into()),
+                    };
+
+                    state.history.push(record);
+                }
+                "interruption" => {
+                    let (kind, at) = value.split_once(' ').ok_or_else(invalid)?;
+                    let interruption = Interruption {
+                        kind: match kind {
+                            "internal" => InterruptionKind::Internal,
+                            "external" => InterruptionKind::External,
+                            "away" => InterruptionKind::Away,
+                            _ => return Err(invalid().into()),
+                        },
+                        at: parse_time(at).flatten().ok_or_else(invalid)?,
+                    };
+
+                    state
+                        .history
+                        .last_mut()
+                        .ok_or_else(invalid)?
+                        .interruptions
+                        .push(interruption);
+                }
+                "pause" => {
+                    let fields: Vec<&str> = value.split(' ').collect();
+                    let pause = match fields[..] {
+                        [started_at, ended_at, reason] => Pause {
+                            started_at: parse_time(started_at).flatten().ok_or_else(invalid)?,
+                            ended_at: parse_time(ended_at).ok_or_else(invalid)?,
+                            reason: parse_pause_reason(reason).ok_or_else(invalid)?,
+                        },
+                        _ => return Err(invalid().into()),
+                    };
+
+                    state
+                        .history
+                        .last_mut()
+                        .ok_or_else(invalid)?
+                        .pauses
+                        .push(pause);
+                }
+                // written by a newer version
+                _ => (),
+            }
+        }
+
+        if let Some(active) = active {
+            state.tasks.select(active);
+        }
+
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_path(name: &str) -> String {
+        env::temp_dir()
+            .join(format!("pomodoro-{}-{}", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn loads_what_was_saved() {
+        let now = Local::now();
+        let mut tasks = TaskList::new();
+
+        tasks.add("Write docs ~3");
+        tasks.add("Review");
+        tasks.toggle_done(0, now);
+        tasks.select(1);
+
+        let state = State {
+            position: 3,
+            status: AppStatus::Scheduled,
+            progress: 1200,
+            phase_duration: 1500,
+            extensions: 1,
+            countdown: 2700,
+            saved_at: now,
+            scheduled_at: NaiveTime::from_hms_opt(9, 0, 0),
+            tasks,
+            history: vec![
+                PhaseRecord {
+                    kind: PhaseKind::Focus,
+                    outcome: PhaseOutcome::Completed,
+                    overrun: 30,
+                    ended_at: now,
+                    interruptions: vec![Interruption {
+                        kind: InterruptionKind::External,
+                        at: now,
+                    }],
+                    task: Some(0),
+                    pauses: vec![Pause {
+                        started_at: now,
+                        ended_at: Some(now),
+                        reason: Some(PauseReason::Call),
+                    }],
+                    active: 1530,
+                },
+                PhaseRecord {
+                    kind: PhaseKind::Rest,
+                    outcome: PhaseOutcome::Skipped,
+                    overrun: 0,
+                    ended_at: now,
+                    interruptions: Vec::new(),
+                    task: None,
+                    pauses: vec![Pause {
+                        started_at: now,
+                        ended_at: None,
+                        reason: Some(PauseReason::Away),
+                    }],
+                    active: 60,
+                },
+            ],
+        };
+        let path = temp_path("round-trip");
+
+        state.save(&path).unwrap();
+        let loaded = State::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap(), state);
+    }
+
+    #[test]
+    fn rejects_invalid_states() {
+        assert!(State::load(&temp_path("missing")).is_err());
+
+        for content in [
+            "position",
+            "position first",
+            "status sleeping",
+            "countdown soon",
+            "scheduled_at 9am",
+            "saved_at yesterday",
+            "task x - Review",
+            "record focus done 0 60 - -",
+            "record focus completed 0 60",
+            "pause - - -",
+        ] {
+            let path = temp_path("invalid");
+
+            fs::write(&path, content).unwrap();
+            let loaded = State::load(&path);
+            fs::remove_file(&path).unwrap();
+
+            assert!(loaded.is_err(), "{}", content);
+        }
+    }
+}
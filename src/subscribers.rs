@@ -0,0 +1,113 @@
+//! Subscribers keeping track of the timers outside of the app.
+
+use chrono::Local;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+
+use crate::events::{Subscriber, TimerEvent};
+
+fn describe(event: &TimerEvent) -> String {
+    match event {
+        TimerEvent::PhaseStarted(phase)
+        | TimerEvent::PhaseRunning(phase)
+        | TimerEvent::ScheduledStart(phase) => phase.get_name().to_string(),
+        TimerEvent::PhaseCompleted { phase, next } | TimerEvent::Skipped { phase, next } => {
+            format!("{} -> {}", phase.get_name(), next.get_name())
+        }
+        TimerEvent::Tick { progress } => progress.to_string(),
+        TimerEvent::Checkpoint(checkpoint) => checkpoint.get_message().to_string(),
+        TimerEvent::GoalReached { completed } => completed.to_string(),
+        TimerEvent::MicroBreakStarted { secs } => secs.to_string(),
+        TimerEvent::Paused
+        | TimerEvent::Resumed
+        | TimerEvent::Stopped
+        | TimerEvent::EndOfDay
+        | TimerEvent::MicroBreakEnded => String::new(),
+    }
+}
+
+/// Appends a timestamped line per event, ticks excluded.
+#[derive(Clone, Debug)]
+pub struct HistoryLog {
+    path: String,
+}
+
+impl HistoryLog {
+    /// Logs to the file at `path`, created if missing.
+    pub fn new(path: &str) -> HistoryLog {
+        HistoryLog {
+            path: path.to_string(),
+        }
+    }
+}
+
+impl Subscriber for HistoryLog {
+    fn on_event(&mut self, timer: &str, event: &TimerEvent) {
+        if let TimerEvent::Tick { .. } = event {
+            return;
+        }
+
+        // logging is best effort, it must never take the timer down
+        if let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+        {
+            let _ = writeln!(
+                file,
+                "{} {} {} {}",
+                Local::now().format("%Y-%m-%d %H:%M:%S"),
+                timer,
+                event.name(),
+                describe(event)
+            );
+        }
+    }
+}
+
+/// Runs a shell command per event, ticks excluded, with the timer in
+/// `POMODORO_TIMER`, the event in `POMODORO_EVENT` and its details in
+/// `POMODORO_DETAILS`.
+#[derive(Debug)]
+pub struct Hook {
+    command: String,
+    children: Vec<Child>, // still running as of the last event
+}
+
+impl Hook {
+    /// Runs `command` with `sh -c`.
+    pub fn new(command: &str) -> Hook {
+        Hook {
+            command: command.to_string(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl Subscriber for Hook {
+    fn on_event(&mut self, timer: &str, event: &TimerEvent) {
+        if let TimerEvent::Tick { .. } = event {
+            return;
+        }
+
+        // the runs that ended are reaped rather than left as zombies
+        self.children
+            .retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+
+        // not waited on, the output would garble the terminal
+        if let Ok(child) = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .env("POMODORO_TIMER", timer)
+            .env("POMODORO_EVENT", event.name())
+            .env("POMODORO_DETAILS", describe(event))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            self.children.push(child);
+        }
+    }
+}
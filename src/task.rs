@@ -0,0 +1,123 @@
+//! Tasks worked on during focus phases.
+
+use chrono::{DateTime, Local};
+
+/// Something to work on, with the number of pomodoros it's expected to take.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Task {
+    name: String,
+    estimate: Option<u64>, // in pomodoros
+    done_at: Option<DateTime<Local>>,
+}
+
+impl Task {
+    /// A task, done if `done_at` is set.
+    pub fn new(name: &str, estimate: Option<u64>, done_at: Option<DateTime<Local>>) -> Task {
+        Task {
+            name: name.to_string(),
+            estimate,
+            done_at,
+        }
+    }
+
+    /// What the task is about.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the task was completed.
+    pub fn is_done(&self) -> bool {
+        self.done_at.is_some()
+    }
+
+    /// When the task was completed.
+    pub fn get_done_at(&self) -> Option<DateTime<Local>> {
+        self.done_at
+    }
+
+    /// In pomodoros.
+    pub fn get_estimate(&self) -> Option<u64> {
+        self.estimate
+    }
+}
+
+/// The tasks of the session, one of them active.
+///
+/// Tasks are only ever added, so their index identifies them in the history.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TaskList {
+    tasks: Vec<Task>,
+    active: Option<usize>,
+}
+
+impl TaskList {
+    /// An empty list.
+    pub fn new() -> TaskList {
+        TaskList {
+            tasks: Vec::new(),
+            active: None,
+        }
+    }
+
+    /// Adds a task by name, "Write docs ~3" estimating it at 3 pomodoros.
+    pub fn add(&mut self, name: &str) {
+        let (name, estimate) = match name
+            .rsplit_once('~')
+            .and_then(|(name, estimate)| Some((name.trim(), estimate.trim().parse().ok()?)))
+        {
+            Some((name, estimate)) => (name, Some(estimate)),
+            None => (name, None),
+        };
+
+        self.push(Task::new(name, estimate, None));
+    }
+
+    /// Adds a task as is.
+    pub fn push(&mut self, task: Task) {
+        self.tasks.push(task);
+    }
+
+    /// Sets the estimate of the task at `index`, in pomodoros.
+    pub fn set_estimate(&mut self, index: usize, estimate: Option<u64>) {
+        if let Some(task) = self.tasks.get_mut(index) {
+            task.estimate = estimate;
+        }
+    }
+
+    /// All tasks, in the order they were added.
+    pub fn get_tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    /// The task at `index`.
+    pub fn get(&self, index: usize) -> Option<&Task> {
+        self.tasks.get(index)
+    }
+
+    /// The index of the task being worked on.
+    pub fn get_active(&self) -> Option<usize> {
+        self.active
+    }
+
+    /// Makes the task at `index` the one worked on, unless it's done.
+    pub fn select(&mut self, index: usize) {
+        if self.tasks.get(index).is_some_and(|task| !task.is_done()) {
+            self.active = Some(index);
+        }
+    }
+
+    /// Marks the task at `index` done at `now`, or not done anymore. A
+    /// completed task can't stay active.
+    pub fn toggle_done(&mut self, index: usize, now: DateTime<Local>) {
+        if let Some(task) = self.tasks.get_mut(index) {
+            task.done_at = match task.done_at {
+                Some(_) => None,
+                None => Some(now),
+            };
+
+            if task.is_done() && self.active == Some(index) {
+                self.active = None;
+            }
+        }
+    }
+}
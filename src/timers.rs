@@ -0,0 +1,83 @@
+//! Several timers running side by side.
+
+use std::time::Duration;
+
+use crate::app::{App, AppStatus};
+
+/// A timer with the name its events and notifications go by.
+pub struct Timer {
+    /// Shown in the UI and passed along with the events.
+    pub name: String,
+    /// The timer itself.
+    pub app: App,
+}
+
+/// Independent timers running side by side, one of them focused to take input.
+pub struct Timers {
+    timers: Vec<Timer>,
+    focused: usize,
+}
+
+impl Timers {
+    /// Starts with the timer `app`, focused, as there is always at least one.
+    pub fn new(name: &str, app: App) -> Timers {
+        Timers {
+            timers: vec![Timer {
+                name: name.to_string(),
+                app,
+            }],
+            focused: 0,
+        }
+    }
+
+    /// Adds a timer after the others.
+    pub fn add(&mut self, name: &str, app: App) {
+        self.timers.push(Timer {
+            name: name.to_string(),
+            app,
+        });
+    }
+
+    /// All timers, in the order they were added.
+    pub fn get_timers(&self) -> &[Timer] {
+        &self.timers
+    }
+
+    /// All timers, in the order they were added.
+    pub fn get_timers_mut(&mut self) -> &mut [Timer] {
+        &mut self.timers
+    }
+
+    /// The timer taking input.
+    pub fn get_focused(&self) -> &Timer {
+        &self.timers[self.focused]
+    }
+
+    /// The timer taking input.
+    pub fn get_focused_mut(&mut self) -> &mut Timer {
+        &mut self.timers[self.focused]
+    }
+
+    /// The position of the focused timer, starting at 0.
+    pub fn get_focused_index(&self) -> usize {
+        self.focused
+    }
+
+    /// Focuses the next timer, going back to the first after the last.
+    pub fn focus_next(&mut self) {
+        self.focused = (self.focused + 1) % self.timers.len();
+    }
+
+    /// Time until any of the timers' display changes, paused ones redraw
+    /// every second.
+    pub fn get_time_to_next_second(&self) -> Duration {
+        self.timers
+            .iter()
+            .map(|timer| match timer.app.get_status() {
+                AppStatus::Paused => Duration::from_secs(1),
+                _ => timer.app.get_time_to_next_second(),
+            })
+            .min()
+            .unwrap_or(Duration::from_secs(1))
+    }
+}
@@ -1,10 +1,21 @@
 use crate::{
-    app::{App, AppEvent, AppStatus},
-    utils::{get_percentage, to_secs, MyResult},
+    app::{
+        App, AppEvent, AppStatus, AutoStart, Interruption, InterruptionKind, PauseReason,
+        PhaseOutcome,
+    },
+    phase::{PhaseColor, PhaseKind},
+    report::{estimation_report, focus_report},
+    state::State,
+    timers::Timers,
+    utils::{format_secs, get_percentage, to_secs, MyResult},
 };
+use chrono::NaiveTime;
 use crossterm::{
     cursor,
-    event::{read, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        read, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        Event, KeyCode,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -14,24 +25,74 @@ use tui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, Gauge, Paragraph, Tabs, Wrap},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph, Tabs, Wrap},
     Frame, Terminal,
 };
 use unicode_width::UnicodeWidthStr;
 
+fn to_color(color: PhaseColor) -> Color {
+    match color {
+        PhaseColor::Yellow => Color::Yellow,
+        PhaseColor::Green => Color::Green,
+        PhaseColor::Cyan => Color::Cyan,
+        PhaseColor::Blue => Color::Blue,
+        PhaseColor::Magenta => Color::Magenta,
+        PhaseColor::Red => Color::Red,
+    }
+}
+
+fn to_app_event(keycode: KeyCode) -> AppEvent {
+    match keycode {
+        KeyCode::Char(char) => match char {
+            'q' => AppEvent::Quit,
+            ' ' => AppEvent::Toggle,
+            's' => AppEvent::Stop,
+            'a' => AppEvent::CycleAutoStart,
+            'n' => AppEvent::Skip,
+            'e' => AppEvent::Extend,
+            '\'' => AppEvent::InternalInterruption,
+            '-' => AppEvent::ExternalInterruption,
+            _ => AppEvent::None,
+        },
+        _ => AppEvent::None,
+    }
+}
+
+// typed in full to end a break held by strict mode
+const ABORT_WORD: &str = "abort";
+
 const BORDERS: [Borders; 4] = [Borders::TOP, Borders::RIGHT, Borders::BOTTOM, Borders::LEFT];
 
 pub enum UiMode {
     Normal,
     EditingWork,
     EditingBreak,
+    EditingLongBreak,
+    Tasks,
+    NewTask,
+    Report,
+    PauseReason,  // right after pausing, until a reason or any other key is pressed
+    ResumeOffer,  // the terminal got focus back after pausing on losing it
+    RestoreOffer, // a session saved by a previous run can be picked back up
+    AbortBreak,   // confirming the end of a break held by strict mode
+    Schedule,     // typing the time of day to start the session at
 }
 
 impl UiMode {
     fn to_usize(ui_mode: &UiMode) -> usize {
         match ui_mode {
-            UiMode::EditingWork | UiMode::Normal => 0,
+            UiMode::EditingWork
+            | UiMode::Normal
+            | UiMode::Tasks
+            | UiMode::NewTask
+            | UiMode::Report
+            | UiMode::PauseReason
+            | UiMode::ResumeOffer
+            | UiMode::RestoreOffer
+            | UiMode::AbortBreak
+            | UiMode::Schedule => 0,
             UiMode::EditingBreak => 1,
+            UiMode::EditingLongBreak => 2,
         }
     }
 
@@ -41,21 +102,36 @@ impl UiMode {
             KeyCode::Char(char) => match char {
                 'w' => return Some(UiMode::EditingWork),
                 'b' => return Some(UiMode::EditingBreak),
+                'l' => return Some(UiMode::EditingLongBreak),
+                't' => return Some(UiMode::Tasks),
                 _ => None,
             },
             _ => None,
         }
     }
+
+    fn is_editing(&self) -> bool {
+        matches!(
+            self,
+            UiMode::EditingWork | UiMode::EditingBreak | UiMode::EditingLongBreak
+        )
+    }
 }
 
 pub struct Ui<'a> {
     title: &'a str,
     stdout: Stdout,
-    inputs: [[String; 3]; 2], //[work time, break time] hours, minutes, seconds
+    inputs: [[String; 3]; 3], //[work time, break time, long break time] hours, minutes, seconds
+    until_inputs: [bool; 3],  // whether the inputs are the time of day the phase ends at
     mode: UiMode,
     tab_focus: usize,
     input_titles: [&'a str; 3], // hours, minutes, seconds
     border_pos: usize,
+    task_cursor: usize,
+    task_input: String,
+    abort_input: String,
+    schedule_input: String,     // HH:MM
+    saved_state: Option<State>, // offered to be restored
 }
 
 impl<'a> Ui<'a> {
@@ -87,11 +163,30 @@ impl<'a> Ui<'a> {
         };
     }
 
+    fn is_until_input(&self) -> bool {
+        self.until_inputs[UiMode::to_usize(&self.mode)]
+    }
+
+    fn toggle_until_input(&mut self) {
+        let index = UiMode::to_usize(&self.mode);
+
+        self.until_inputs[index] = !self.until_inputs[index];
+    }
+
     fn set_input(&mut self, c: char) {
         if c.is_numeric() {
             let tab_focus = self.tab_focus;
 
-            if tab_focus == 1 || tab_focus == 2 {
+            if tab_focus == 0 && self.is_until_input() {
+                let mut input_clone = self.get_input().clone();
+                input_clone.push(c);
+
+                let hours: u64 = input_clone.parse().unwrap_or(0);
+
+                if hours < 24 {
+                    self.inputs[UiMode::to_usize(&self.mode)][self.tab_focus].push(c);
+                }
+            } else if tab_focus == 1 || tab_focus == 2 {
                 let mut input_clone = self.get_input().clone();
                 input_clone.push(c);
 
@@ -130,38 +225,72 @@ impl<'a> Ui<'a> {
         self.inputs[UiMode::to_usize(&self.mode)].clone()
     }
 
-    pub fn new(title: &'a str, input_titles: [&'a str; 3], inputs: [[String; 3]; 2]) -> Ui<'a> {
+    pub fn new(
+        title: &'a str,
+        input_titles: [&'a str; 3],
+        inputs: [[String; 3]; 3],
+        until_inputs: [bool; 3],
+    ) -> Ui<'a> {
         Ui {
             title,
             stdout: io::stdout(),
             inputs,
+            until_inputs,
             mode: UiMode::Normal,
             tab_focus: 0,
             input_titles,
             border_pos: 0,
+            task_cursor: 0,
+            task_input: String::new(),
+            abort_input: String::new(),
+            schedule_input: String::new(),
+            saved_state: None,
         }
     }
 
+    pub fn offer_restore(&mut self, state: State) {
+        self.saved_state = Some(state);
+        self.mode = UiMode::RestoreOffer;
+    }
+
+    pub fn is_restore_offered(&self) -> bool {
+        self.saved_state.is_some()
+    }
+
     pub fn setup_terminal(&mut self) -> MyResult<()> {
-        execute!(self.stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        execute!(
+            self.stdout,
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableFocusChange
+        )?;
         enable_raw_mode()?;
 
         Ok(())
     }
 
     pub fn cleanup(&mut self) -> MyResult<()> {
-        execute!(self.stdout, LeaveAlternateScreen, DisableMouseCapture)?;
+        execute!(
+            self.stdout,
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableFocusChange
+        )?;
         execute!(self.stdout, cursor::Show)?;
         disable_raw_mode()?;
 
         Ok(())
     }
 
+    // phases counting up have no percentage, `progress_percent` is None and
+    // the label is shown instead
     fn render_gauge(
         &self,
         frame: &mut Frame<CrosstermBackend<Stdout>>,
         title: &str,
-        progress_percent: u16,
+        color: Color,
+        progress_percent: Option<u16>,
+        label: String,
     ) {
         let mut gauge_size = frame.size().clone();
         gauge_size.height = gauge_size.height / 8;
@@ -169,12 +298,22 @@ impl<'a> Ui<'a> {
         gauge_size.x = (frame.size().width / 2) - (gauge_size.width / 2);
         gauge_size.y = (frame.size().height / 2) - (gauge_size.height / 2);
 
-        let gauge = Gauge::default()
-            .block(Block::default().title(title).borders(Borders::ALL))
-            .gauge_style(Style::default().fg(Color::Yellow))
-            .percent(progress_percent);
+        let block = Block::default().title(title).borders(Borders::ALL);
 
-        frame.render_widget(gauge, gauge_size);
+        if let Some(progress_percent) = progress_percent {
+            let gauge = Gauge::default()
+                .block(block)
+                .gauge_style(Style::default().fg(color))
+                .percent(progress_percent);
+
+            frame.render_widget(gauge, gauge_size);
+        } else {
+            let paragraph = Paragraph::new(Span::styled(label, Style::default().fg(color)))
+                .block(block)
+                .alignment(Alignment::Center);
+
+            frame.render_widget(paragraph, gauge_size);
+        }
     }
 
     fn render_timer(
@@ -182,6 +321,8 @@ impl<'a> Ui<'a> {
         frame: &mut Frame<CrosstermBackend<Stdout>>,
         formatted_progress: String,
         show_all_borders: bool,
+        color: Color,
+        interruptions: &[Interruption],
     ) {
         let mut block_size = frame.size().clone();
         let border = if show_all_borders {
@@ -203,9 +344,26 @@ impl<'a> Ui<'a> {
 
         frame.render_widget(block, block_size);
 
-        let paragraph = Paragraph::new(Span::raw(formatted_progress))
-            .style(Style::default().fg(Color::White))
-            .alignment(Alignment::Center);
+        // tally marks, ' for internal and - for external interruptions
+        let mut text = vec![Span::styled(formatted_progress, Style::default().fg(color))];
+
+        if let Some(last) = interruptions.last() {
+            let tally: String = interruptions
+                .iter()
+                .map(|interruption| match interruption.kind {
+                    InterruptionKind::Internal => '\'',
+                    InterruptionKind::External => '-',
+                    InterruptionKind::Away => '~',
+                })
+                .collect();
+
+            text.push(Span::styled(
+                format!("  {} (last {})", tally, last.at.format("%H:%M")),
+                Style::default().fg(Color::Magenta),
+            ));
+        }
+
+        let paragraph = Paragraph::new(Spans::from(text)).alignment(Alignment::Center);
 
         let mut paragraph_size = block_size.clone();
         paragraph_size.height = paragraph_size.height / 2;
@@ -240,8 +398,13 @@ impl<'a> Ui<'a> {
                 ])
             })
             .collect();
+        let tabs_title = if self.is_until_input() {
+            "Ends at"
+        } else {
+            "Duration"
+        };
         let tabs = Tabs::new(titles)
-            .block(Block::default().borders(Borders::ALL))
+            .block(Block::default().borders(Borders::ALL).title(tabs_title))
             .select(selected)
             .style(Style::default().fg(Color::Cyan))
             .highlight_style(
@@ -275,20 +438,260 @@ impl<'a> Ui<'a> {
         );
     }
 
-    fn render_help(&self, frame: &mut Frame<CrosstermBackend<Stdout>>) {
+    fn render_task(&self, frame: &mut Frame<CrosstermBackend<Stdout>>, name: &str) {
+        // the line right above the timer
+        let mut size = frame.size();
+        size.width /= 2;
+        size.x = size.width / 2;
+        size.y = (frame.size().height / 4).saturating_sub(1);
+        size.height = 1;
+
+        let paragraph = Paragraph::new(Span::styled(
+            format!("Task: {}", name),
+            Style::default().fg(Color::Cyan),
+        ))
+        .alignment(Alignment::Center);
+
+        frame.render_widget(paragraph, size);
+    }
+
+    fn render_micro_break(&self, frame: &mut Frame<CrosstermBackend<Stdout>>, secs: u64) {
+        let mut size = frame.size();
+        size.width /= 2;
+        size.x = size.width / 2;
+        size.height = 3;
+        size.y = frame.size().height.saturating_sub(size.height) / 2;
+
+        let paragraph = Paragraph::new(Span::styled(
+            format!("Look into the distance, {}s", secs),
+            Style::default().fg(Color::Green),
+        ))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+
+        frame.render_widget(Clear, size);
+        frame.render_widget(paragraph, size);
+    }
+
+    fn render_tasks(&self, frame: &mut Frame<CrosstermBackend<Stdout>>, app: &App) {
+        let mut size = frame.size();
+        size.width /= 2;
+        size.x = size.width / 2;
+        size.height = (size.height as f64 * 0.6).floor() as u16;
+        size.y = frame.size().height / 8;
+
+        let mut lines: Vec<Spans> = app
+            .get_tasks()
+            .iter()
+            .enumerate()
+            .map(|(i, task)| {
+                let marker = if app.get_active_task_index() == Some(i) {
+                    "> "
+                } else {
+                    "  "
+                };
+                let check = if task.is_done() { "[x] " } else { "[ ] " };
+                let actual = app.get_task_pomodoros(i);
+                let mut style = Style::default();
+
+                // overran its estimate
+                if task
+                    .get_estimate()
+                    .is_some_and(|estimate| actual > estimate)
+                {
+                    style = style.fg(Color::Red);
+                }
+
+                if i == self.task_cursor {
+                    style = style.add_modifier(Modifier::BOLD).bg(Color::Black);
+                }
+
+                let estimate = task
+                    .get_estimate()
+                    .map_or("-".to_string(), |estimate| estimate.to_string());
+
+                Spans::from(Span::styled(
+                    format!(
+                        "{}{}{} ({}/{})",
+                        marker,
+                        check,
+                        task.get_name(),
+                        actual,
+                        estimate
+                    ),
+                    style,
+                ))
+            })
+            .collect();
+
+        if matches!(self.mode, UiMode::NewTask) {
+            lines.push(Spans::from(Span::styled(
+                format!("  New task: {}", self.task_input),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title("Tasks (actual/estimated pomodoros)")
+                    .borders(Borders::ALL),
+            )
+            .style(Style::default().bg(Color::Reset));
+
+        frame.render_widget(Clear, size);
+        frame.render_widget(paragraph, size);
+    }
+
+    fn render_report(&self, frame: &mut Frame<CrosstermBackend<Stdout>>, app: &App) {
+        let mut size = frame.size();
+        size.width /= 2;
+        size.x = size.width / 2;
+        size.height = (size.height as f64 * 0.6).floor() as u16;
+        size.y = frame.size().height / 8;
+
+        let mut lines = vec![Spans::from(Span::styled(
+            "Date        Tasks  Estimated  Actual  Accuracy",
+            Style::default().add_modifier(Modifier::BOLD),
+        ))];
+
+        for row in estimation_report(app) {
+            lines.push(Spans::from(Span::raw(format!(
+                "{}  {:>5}  {:>9}  {:>6}  {:>7}%",
+                row.date.format("%Y-%m-%d"),
+                row.tasks,
+                row.estimated,
+                row.actual,
+                row.accuracy
+            ))));
+        }
+
+        lines.push(Spans::from(Span::raw("")));
+        lines.push(Spans::from(Span::styled(
+            "Date        Phases   Focused    Paused",
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+
+        for row in focus_report(app) {
+            let [focused_hours, focused_minutes, focused_seconds] = format_secs(row.focused);
+            let [paused_hours, paused_minutes, paused_seconds] = format_secs(row.paused);
+
+            lines.push(Spans::from(Span::raw(format!(
+                "{}  {:>6}  {:02}:{:02}:{:02}  {:02}:{:02}:{:02}",
+                row.date.format("%Y-%m-%d"),
+                row.phases,
+                focused_hours,
+                focused_minutes,
+                focused_seconds,
+                paused_hours,
+                paused_minutes,
+                paused_seconds
+            ))));
+        }
+
+        let paragraph =
+            Paragraph::new(lines).block(Block::default().title("Reports").borders(Borders::ALL));
+
+        frame.render_widget(Clear, size);
+        frame.render_widget(paragraph, size);
+    }
+
+    fn render_help(
+        &self,
+        frame: &mut Frame<CrosstermBackend<Stdout>>,
+        auto_start: AutoStart,
+        several_timers: bool,
+        locked: bool,
+    ) {
         let mut text = vec![
             Span::raw("[q] Quit| "),
             Span::raw("[w] Set work time| "),
             Span::raw("[b] Set break time| "),
-            Span::raw("[Space] Start/Pause"),
+            Span::raw("[l] Set long break time| "),
+            Span::raw("[Space] Start/Pause| "),
+            Span::raw("[n] Skip/Done| "),
+            Span::raw("[e] Extend| "),
+            Span::raw("['/-] Interruption| "),
+            Span::raw("[t] Tasks| "),
+            Span::raw("[@] Start at| "),
+            Span::raw(format!("[a] Auto-start: {}", auto_start.label())),
         ];
 
-        if !matches!(self.mode, UiMode::Normal) {
+        if several_timers {
+            text.push(Span::raw("| [Tab] Next timer"));
+        }
+
+        if locked {
+            text = vec![
+                Span::styled(
+                    " Strict mode: no skipping, stopping, pausing or editing the break| ",
+                    Style::default().fg(Color::Red),
+                ),
+                Span::raw("[Space] Start| "),
+                Span::raw("[e] Extend| "),
+                Span::raw("[t] Tasks| "),
+                Span::raw("[x] Abort| "),
+                Span::raw("[q] Quit"),
+            ];
+        }
+
+        if self.mode.is_editing() {
             text = vec![
                 Span::raw(" [Esc/Enter] Close| "),
                 Span::raw("[Tab] Next| "),
-                Span::raw("[Shift-Tab] Previous"),
+                Span::raw("[Shift-Tab] Previous| "),
+                Span::raw("[u] Duration/Ends at"),
             ];
+        } else if matches!(self.mode, UiMode::Tasks) {
+            text = vec![
+                Span::raw(" [Esc] Close| "),
+                Span::raw("[Up/Down] Move| "),
+                Span::raw("[Enter] Work on it| "),
+                Span::raw("[n] New task (name ~estimate)| "),
+                Span::raw("[+/-] Estimate| "),
+                Span::raw("[d] Done/Undone| "),
+                Span::raw("[r] Report"),
+            ];
+        } else if matches!(self.mode, UiMode::Report) {
+            text = vec![Span::raw(" [Esc] Close")];
+        } else if matches!(self.mode, UiMode::PauseReason) {
+            text = vec![Span::raw(" Why the pause? ")];
+
+            for (i, reason) in PauseReason::ALL.iter().enumerate() {
+                text.push(Span::raw(format!("[{}] {}| ", i + 1, reason.label())));
+            }
+            text.push(Span::raw("[Other keys] No reason"));
+        } else if let (UiMode::RestoreOffer, Some(state)) = (&self.mode, &self.saved_state) {
+            text = vec![
+                Span::raw(format!(
+                    " Resume the session saved at {}? ",
+                    state.saved_at.format("%Y-%m-%d %H:%M")
+                )),
+                Span::raw("[y] Resume| "),
+                Span::raw("[Other keys] Start over"),
+            ];
+        } else if matches!(self.mode, UiMode::Schedule) {
+            text = vec![
+                Span::raw(format!(" Start at (HH:MM): {}| ", self.schedule_input)),
+                Span::raw("[Enter] Schedule| "),
+                Span::raw("[Esc] Cancel"),
+            ];
+        } else if matches!(self.mode, UiMode::AbortBreak) {
+            text = vec![
+                Span::raw(format!(
+                    " Type `{}` and Enter to end the break: {}| ",
+                    ABORT_WORD, self.abort_input
+                )),
+                Span::raw("[Esc] Cancel"),
+            ];
+        } else if matches!(self.mode, UiMode::ResumeOffer) {
+            text = vec![
+                Span::raw(" Welcome back, paused while you were away| "),
+                Span::raw("[Space] Resume| "),
+                Span::raw("[Other keys] Stay paused"),
+            ];
+        } else if matches!(self.mode, UiMode::NewTask) {
+            text = vec![Span::raw(" [Esc] Cancel| "), Span::raw("[Enter] Add")];
         };
 
         let paragraph = Paragraph::new(Spans::from(text));
@@ -305,60 +708,469 @@ impl<'a> Ui<'a> {
     pub fn draw(
         &mut self,
         terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-        app: &mut App,
+        timers: &mut Timers,
     ) -> MyResult<()> {
+        // the timers in the background, shown next to the focused one's name
+        let others: Vec<String> = timers
+            .get_timers()
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != timers.get_focused_index())
+            .map(|(_, timer)| format!("{} {}", timer.name, timer.app.get_formatted_progress()))
+            .collect();
+        let timer_count = timers.get_timers().len();
+        let focused = timers.get_focused_mut();
+        let name = if timer_count > 1 {
+            format!("{} [{}]", self.title, focused.name)
+        } else {
+            self.title.to_string()
+        };
+        let app = &mut focused.app;
+
         terminal.draw(|frame| {
-            let block = Block::default().title(self.title).borders(Borders::ALL);
+            let breaks = app
+                .get_history()
+                .iter()
+                .filter(|record| matches!(record.kind, PhaseKind::Rest));
+            let skipped_breaks = breaks
+                .clone()
+                .filter(|record| matches!(record.outcome, PhaseOutcome::Skipped))
+                .count();
+            let [hours, minutes, seconds] =
+                format_secs(app.get_history().iter().map(|record| record.overrun).sum());
+            let interruptions: usize = app
+                .get_history()
+                .iter()
+                .map(|record| record.interruptions.len())
+                .sum();
+            let mut title = format!(
+                "{} | Breaks taken: {}, skipped: {} | Overtime: {:02}:{:02}:{:02} | Interruptions: {}",
+                name,
+                breaks.count() - skipped_breaks,
+                skipped_breaks,
+                hours,
+                minutes,
+                seconds,
+                interruptions
+            );
+
+            let [hours, minutes, seconds] = format_secs(app.get_focused_today());
+            title = format!(
+                "{} | Focused today: {:02}:{:02}:{:02}",
+                title, hours, minutes, seconds
+            );
+
+            if app.get_daily_goal() > 0 {
+                title = format!(
+                    "{} | Today: {} / {}",
+                    title,
+                    app.get_completed_today(),
+                    app.get_daily_goal()
+                );
+            }
+
+            if !others.is_empty() {
+                title = format!("{} | {}", title, others.join(", "));
+            }
+
+            let block = Block::default().title(title).borders(Borders::ALL);
 
             frame.render_widget(block, frame.size());
 
-            let title;
-            let max;
+            let phase = app.get_phase();
+            let max = app.get_phase_duration();
 
-            if app.get_is_working() {
-                title = "Work time";
-                max = app.get_work_duration();
+            let sequence_len = app.get_sequence().len();
+            let mut title = if sequence_len > 1 {
+                format!(
+                    "{} ({}/{})",
+                    phase.get_name(),
+                    app.get_position(),
+                    sequence_len
+                )
             } else {
-                title = "Break time";
-                max = app.get_break_duration();
+                phase.get_name().to_string()
             };
 
-            self.render_gauge(
+            if let Some(until) = phase.get_until() {
+                title = format!("{} until {}", title, until.format("%H:%M"));
+            }
+
+            if app.get_extensions() > 0 {
+                title = format!("{} +{}", title, app.get_extensions());
+            }
+
+            if phase.is_count_up() {
+                let [hours, minutes, seconds] = format_secs(app.get_earned_break_secs());
+
+                self.render_gauge(
+                    frame,
+                    &title,
+                    to_color(phase.get_color()),
+                    None,
+                    format!("Break earned {:02}:{:02}:{:02}", hours, minutes, seconds),
+                );
+            } else {
+                self.render_gauge(
+                    frame,
+                    &title,
+                    to_color(phase.get_color()),
+                    Some(get_percentage(max.saturating_sub(app.get_remaining_secs()), max)),
+                    String::new(),
+                );
+            }
+
+            let (formatted_progress, color) = match app.get_status() {
+                AppStatus::Starting => (
+                    format!("Starting in {}…", app.get_countdown_secs()),
+                    Color::White,
+                ),
+                AppStatus::Scheduled => {
+                    let [hours, minutes, seconds] = format_secs(app.get_countdown_secs());
+                    let at = app
+                        .get_scheduled_at()
+                        .map_or(String::new(), |at| at.format("%H:%M").to_string());
+
+                    (
+                        format!(
+                            "Starts at {} in {:02}:{:02}:{:02}",
+                            at, hours, minutes, seconds
+                        ),
+                        Color::White,
+                    )
+                }
+                AppStatus::Overtime => (
+                    format!("+{} (Space to continue)", app.get_formatted_progress()),
+                    Color::Red,
+                ),
+                _ if phase.is_count_up() => (
+                    format!("+{}", app.get_formatted_progress()),
+                    Color::LightGreen,
+                ),
+                _ => (app.get_formatted_progress(), Color::White),
+            };
+            let paused: u64 = app
+                .get_pauses()
+                .iter()
+                .map(|pause| pause.get_duration(app.get_now()))
+                .sum();
+            let formatted_progress = if paused > 0 {
+                let [hours, minutes, seconds] = format_secs(paused);
+                let reason = app
+                    .get_pauses()
+                    .last()
+                    .filter(|pause| pause.ended_at.is_none())
+                    .and_then(|pause| pause.reason)
+                    .map_or(String::new(), |reason| format!(" ({})", reason.label()));
+
+                format!(
+                    "{} | paused {:02}:{:02}:{:02}{}",
+                    formatted_progress, hours, minutes, seconds, reason
+                )
+            } else {
+                formatted_progress
+            };
+            let (formatted_progress, color) = match app.get_end_of_day_secs() {
+                Some(secs) => {
+                    let [hours, minutes, seconds] = format_secs(secs);
+                    let formatted_progress = format!(
+                        "{} | end of day in {:02}:{:02}:{:02}",
+                        formatted_progress, hours, minutes, seconds
+                    );
+
+                    if app.is_short_on_time() {
+                        (
+                            format!("{}, short of a full work phase", formatted_progress),
+                            Color::Yellow,
+                        )
+                    } else {
+                        (formatted_progress, color)
+                    }
+                }
+                None => (formatted_progress, color),
+            };
+
+            self.render_timer(
                 frame,
-                title,
-                get_percentage(max - app.get_progress_secs(), max),
+                formatted_progress,
+                matches!(
+                    app.get_status(),
+                    AppStatus::Paused | AppStatus::Starting | AppStatus::Scheduled
+                ),
+                color,
+                app.get_interruptions(),
             );
 
-            self.render_timer(
+            self.render_help(
                 frame,
-                app.get_formatted_progress(),
-                matches!(app.get_status(), AppStatus::Paused),
+                app.get_auto_start(),
+                timer_count > 1,
+                app.is_locked(),
             );
 
-            self.render_help(frame);
+            if let Some(task) = app.get_active_task() {
+                self.render_task(frame, task.get_name());
+            }
 
-            if !matches!(self.mode, UiMode::Normal) {
+            if let Some(secs) = app.get_micro_break_secs() {
+                self.render_micro_break(frame, secs);
+            }
+
+            if self.mode.is_editing() {
                 self.render_input(frame);
+            } else if matches!(self.mode, UiMode::Tasks | UiMode::NewTask) {
+                self.render_tasks(frame, app);
+            } else if matches!(self.mode, UiMode::Report) {
+                self.render_report(frame, app);
             }
         })?;
 
         Ok(())
     }
 
-    pub fn handle_keypress(&mut self, app: &mut App) -> MyResult<()> {
+    fn handle_task_keypress(&mut self, app: &mut App, keycode: KeyCode) {
+        if matches!(self.mode, UiMode::Report) {
+            if matches!(keycode, KeyCode::Esc | KeyCode::Char('r')) {
+                self.mode = UiMode::Tasks;
+            }
+
+            return;
+        }
+
+        if matches!(self.mode, UiMode::NewTask) {
+            match keycode {
+                KeyCode::Char(c) => {
+                    self.task_input.push(c);
+                }
+                KeyCode::Backspace => {
+                    self.task_input.pop();
+                }
+                KeyCode::Enter => {
+                    let name = self.task_input.trim().to_string();
+
+                    if !name.is_empty() {
+                        app.add_task(&name);
+                        self.task_cursor = app.get_tasks().len() - 1;
+                    }
+                    self.task_input.clear();
+                    self.mode = UiMode::Tasks;
+                }
+                KeyCode::Esc => {
+                    self.task_input.clear();
+                    self.mode = UiMode::Tasks;
+                }
+                _ => (),
+            }
+
+            return;
+        }
+
+        match keycode {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.task_cursor = self.task_cursor.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.task_cursor + 1 < app.get_tasks().len() => {
+                self.task_cursor += 1;
+            }
+            KeyCode::Enter => {
+                app.select_task(self.task_cursor);
+                self.mode = UiMode::Normal;
+            }
+            KeyCode::Char('n') => {
+                self.mode = UiMode::NewTask;
+            }
+            KeyCode::Char('d') => {
+                app.toggle_task_done(self.task_cursor);
+            }
+            KeyCode::Char('+') => {
+                let estimate = app
+                    .get_tasks()
+                    .get(self.task_cursor)
+                    .and_then(|task| task.get_estimate());
+
+                app.set_task_estimate(self.task_cursor, Some(estimate.unwrap_or(0) + 1));
+            }
+            KeyCode::Char('-') => {
+                let estimate = app
+                    .get_tasks()
+                    .get(self.task_cursor)
+                    .and_then(|task| task.get_estimate());
+
+                // going below one removes the estimate
+                app.set_task_estimate(
+                    self.task_cursor,
+                    estimate.filter(|estimate| *estimate > 1).map(|e| e - 1),
+                );
+            }
+            KeyCode::Char('r') => {
+                self.mode = UiMode::Report;
+            }
+            KeyCode::Esc | KeyCode::Char('t') => {
+                self.mode = UiMode::Normal;
+            }
+            _ => (),
+        }
+    }
+
+    // whether the event may have changed the timers' state, mouse moves and
+    // the like don't
+    pub fn handle_keypress(&mut self, timers: &mut Timers) -> MyResult<bool> {
         // `read()` blocks until an `Event` is available
-        if let Event::Key(event) = read()? {
+        let event = read()?;
+        let changes = matches!(event, Event::Key(_) | Event::FocusLost);
+
+        if let (UiMode::Normal, Event::Key(key)) = (&self.mode, &event) {
+            if key.code == KeyCode::Tab {
+                timers.focus_next();
+
+                return Ok(false);
+            }
+        }
+
+        // the inputs hold the first timer's durations, the others are set on the command line
+        let editable = timers.get_focused_index() == 0;
+        let app = &mut timers.get_focused_mut().app;
+
+        match event {
+            Event::FocusLost => {
+                app.on_focus_lost();
+            }
+            Event::FocusGained
+                if app.get_paused_while_away() && matches!(self.mode, UiMode::Normal) =>
+            {
+                self.mode = UiMode::ResumeOffer;
+            }
+            _ => (),
+        }
+
+        if let Event::Key(event) = event {
             let keycode = event.code;
 
-            if let Some(ui_mode) = UiMode::from_keycode(&keycode) {
+            if matches!(self.mode, UiMode::ResumeOffer) {
+                self.mode = UiMode::Normal;
+            }
+
+            if matches!(self.mode, UiMode::RestoreOffer) {
+                self.mode = UiMode::Normal;
+
+                if let (KeyCode::Char('y'), Some(state)) = (keycode, self.saved_state.take()) {
+                    app.restore(&state);
+                }
+
+                return Ok(true);
+            }
+
+            if matches!(self.mode, UiMode::AbortBreak) {
+                match keycode {
+                    KeyCode::Char(c) => {
+                        self.abort_input.push(c);
+                    }
+                    KeyCode::Backspace => {
+                        self.abort_input.pop();
+                    }
+                    KeyCode::Enter => {
+                        if self.abort_input == ABORT_WORD {
+                            app.abort_break();
+                        }
+                        self.abort_input.clear();
+                        self.mode = UiMode::Normal;
+                    }
+                    KeyCode::Esc => {
+                        self.abort_input.clear();
+                        self.mode = UiMode::Normal;
+                    }
+                    _ => (),
+                }
+
+                return Ok(true);
+            }
+
+            if matches!(self.mode, UiMode::Schedule) {
+                match keycode {
+                    KeyCode::Char(c) => {
+                        self.schedule_input.push(c);
+                    }
+                    KeyCode::Backspace => {
+                        self.schedule_input.pop();
+                    }
+                    KeyCode::Enter => {
+                        if let Ok(at) = NaiveTime::parse_from_str(&self.schedule_input, "%H:%M") {
+                            app.schedule(at);
+                        }
+                        self.schedule_input.clear();
+                        self.mode = UiMode::Normal;
+                    }
+                    KeyCode::Esc => {
+                        self.schedule_input.clear();
+                        self.mode = UiMode::Normal;
+                    }
+                    _ => (),
+                }
+
+                return Ok(true);
+            }
+
+            if matches!(self.mode, UiMode::Normal)
+                && keycode == KeyCode::Char('@')
+                && !app.is_locked()
+            {
+                self.mode = UiMode::Schedule;
+
+                return Ok(true);
+            }
+
+            // strict mode may have taken hold while editing, once a break auto-started
+            if app.is_locked() {
+                if self.mode.is_editing() {
+                    self.mode = UiMode::Normal;
+                }
+
+                if matches!(self.mode, UiMode::Normal) && keycode == KeyCode::Char('x') {
+                    self.mode = UiMode::AbortBreak;
+
+                    return Ok(true);
+                }
+            }
+
+            if matches!(self.mode, UiMode::PauseReason) {
+                self.mode = UiMode::Normal;
+
+                let reason = match keycode {
+                    KeyCode::Char(c) => c
+                        .to_digit(10)
+                        .and_then(|i| PauseReason::ALL.get((i as usize).checked_sub(1)?)),
+                    _ => None,
+                };
+
+                if let Some(reason) = reason {
+                    app.set_pause_reason(*reason);
+
+                    return Ok(true);
+                }
+            }
+
+            if matches!(self.mode, UiMode::Tasks | UiMode::NewTask | UiMode::Report) {
+                self.handle_task_keypress(app, keycode);
+
+                return Ok(true);
+            }
+
+            if let Some(ui_mode) = UiMode::from_keycode(&keycode)
+                .filter(|mode| (editable && !app.is_locked()) || !mode.is_editing())
+            {
                 self.mode = ui_mode;
             }
 
-            if !matches!(self.mode, UiMode::Normal) {
+            if matches!(self.mode, UiMode::Tasks) {
+                self.task_cursor = app.get_active_task_index().unwrap_or(0);
+            } else if self.mode.is_editing() {
                 self.select_tab(&keycode);
                 app.stop();
 
                 match keycode {
+                    KeyCode::Char('u') => {
+                        self.toggle_until_input();
+                    }
                     KeyCode::Char(c) => {
                         self.set_input(c);
                     }
@@ -370,31 +1182,38 @@ impl<'a> Ui<'a> {
 
                 let [hours, minutes, secs] =
                     self.get_inputs().map(|e| e.parse::<u64>().unwrap_or(0));
-                let secs = to_secs(hours, minutes, secs);
+                let index = UiMode::to_usize(&self.mode);
 
-                match self.mode {
-                    UiMode::EditingWork => {
-                        app.set_is_working(true);
-                        app.set_work_duration(secs);
+                if self.is_until_input() {
+                    if let Some(until) =
+                        NaiveTime::from_hms_opt(hours as u32, minutes as u32, secs as u32)
+                    {
+                        app.set_phase_until(index, until);
                     }
-                    UiMode::EditingBreak => {
-                        app.set_is_working(false);
-                        app.set_break_duration(secs);
-                    }
-                    _ => (),
+                } else {
+                    app.set_phase_duration(index, to_secs(hours, minutes, secs));
                 }
 
                 // reset tab focus
                 if matches!(keycode, KeyCode::Esc) {
-                    app.set_is_working(true);
+                    app.stop();
                     self.set_initial_tab_focus();
                 }
             } else {
-                let event = AppEvent::from_keycode(keycode)?;
+                let event = to_app_event(keycode);
+                let was_running = matches!(app.get_status(), AppStatus::Running);
+                let toggled = matches!(event, AppEvent::Toggle);
                 app.on(event);
+                // strict mode may have refused it
+                let pausing =
+                    toggled && was_running && matches!(app.get_status(), AppStatus::Paused);
+
+                if pausing {
+                    self.mode = UiMode::PauseReason;
+                }
             }
         }
 
-        Ok(())
+        Ok(changes)
     }
 }
@@ -1,4 +1,7 @@
+use chrono::NaiveTime;
 use std::error::Error;
+use std::time::Duration;
+/// The result of anything that can fail in this crate.
 pub type MyResult<T> = Result<T, Box<dyn Error>>;
 
 pub const SECONDS_IN_MINUTES: u64 = 60;
@@ -21,6 +24,35 @@ pub fn to_secs(hours: u64, minutes: u64, secs: u64) -> u64 {
     hours_in_seconds + minutes_in_seconds + secs
 }
 
+#[cfg(feature = "cli")]
 pub fn get_percentage(value: u64, total_value: u64) -> u16 {
-    ((value as f32 / total_value as f32) * 100.0) as u16
+    // capped, as a gauge can't go past full
+    ((value as f32 / total_value as f32) * 100.0).min(100.0) as u16
+}
+
+// rounded up, so a countdown only reads 0 once it is over
+pub fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+// time until `ceil_secs` of a countdown changes
+pub fn time_to_next_second(duration: Duration) -> Duration {
+    let subsec = Duration::from_nanos(duration.subsec_nanos().into());
+
+    if subsec.is_zero() {
+        Duration::from_secs(1).min(duration)
+    } else {
+        subsec
+    }
+}
+
+// seconds from `now` until the next time the clock reads `time`
+pub fn secs_until(time: NaiveTime, now: NaiveTime) -> u64 {
+    let secs = time.signed_duration_since(now).num_seconds();
+
+    if secs > 0 {
+        secs as u64
+    } else {
+        (secs + (24 * SECONDS_IN_HOURS) as i64) as u64
+    }
 }